@@ -0,0 +1,45 @@
+use std::{hint::black_box, rc::Rc, sync::Arc};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use exploring_rust::monoid::{
+    endomorphism::{ComposeChain, EndomorphismChain, SyncEndomorphism},
+    ComposeEndomorphism, Endomorphism, Monoid,
+};
+
+fn compose(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compose_and_apply");
+    // nesting closures much deeper overflows the stack
+    for n in [100, 10_000] {
+        group.bench_with_input(BenchmarkId::new("nested_closures", n), &n, |b, &n| {
+            b.iter(|| {
+                let f = ComposeEndomorphism::accumulate(
+                    (0..n).map(|i| Rc::new(move |x: usize| x ^ i) as Endomorphism<usize>),
+                );
+                f(black_box(0))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("rc_chain", n), &n, |b, &n| {
+            b.iter(|| {
+                let f = ComposeChain::accumulate((0..n).map(|i| {
+                    let f: Endomorphism<usize> = Rc::new(move |x| x ^ i);
+                    EndomorphismChain::new(f)
+                }));
+                f.apply(black_box(0))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("arc_chain", n), &n, |b, &n| {
+            b.iter(|| {
+                let f = ComposeChain::accumulate((0..n).map(|i| {
+                    let f: SyncEndomorphism<usize> = Arc::new(move |x| x ^ i);
+                    EndomorphismChain::new(f)
+                }));
+                f.apply(black_box(0))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, compose);
+criterion_main!(benches);
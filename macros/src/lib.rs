@@ -0,0 +1,47 @@
+//! Procedural macros for `exploring_rust`, use them through its re-exports.
+
+use proc_macro2::{Punct, Spacing, TokenStream};
+use quote::{quote, TokenStreamExt};
+use syn::{parse_macro_input, LitInt};
+
+/// Creates the `Zero`/`Succ` type of an integer literal, e.g. `peano!(12)` or `peano!(0xff)`.
+///
+/// Every `Succ` is a level of recursion for the trait solver, so reading the `Value` of numbers
+/// above ~120 or doing arithmetic on them needs a `#![recursion_limit]` above the number. Use
+/// `binary!` for numbers that are too big for that.
+#[proc_macro]
+pub fn peano(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let literal = parse_macro_input!(input as LitInt);
+    match peano_type(&literal) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.into_compile_error().into(),
+    }
+}
+
+fn peano_type(literal: &LitInt) -> syn::Result<TokenStream> {
+    if !literal.suffix().is_empty() {
+        return Err(syn::Error::new(
+            literal.span(),
+            "expected an integer literal without a suffix",
+        ));
+    }
+    // `Value::VALUE` is a `usize`
+    let n: usize = literal.base10_parse().map_err(|_| {
+        syn::Error::new(
+            literal.span(),
+            "number too large for a Peano number, use `binary!` instead",
+        )
+    })?;
+
+    // `Succ<Succ<...<Zero>...>>` built flat, nesting `quote!` calls would be quadratic
+    let mut tokens = TokenStream::new();
+    for _ in 0..n {
+        tokens.extend(quote!(::exploring_rust::peano::Succ));
+        tokens.append(Punct::new('<', Spacing::Alone));
+    }
+    tokens.extend(quote!(::exploring_rust::peano::Zero));
+    for _ in 0..n {
+        tokens.append(Punct::new('>', Spacing::Alone));
+    }
+    Ok(tokens)
+}
@@ -1,37 +1,11 @@
 #![recursion_limit = "256"]
 
-use std::marker::PhantomData;
-
-struct Zero;
-struct Succ<N>(PhantomData<N>);
-
-trait Value {
-    const VALUE: usize;
-}
-impl Value for Zero {
-    const VALUE: usize = 0;
-}
-impl<N> Value for Succ<N>
-where
-    N: Value,
-{
-    const VALUE: usize = 1 + <N as Value>::VALUE;
-}
-
-trait CalculateAdd<Num> {
-    type Output;
-}
-impl<N> CalculateAdd<N> for Zero {
-    type Output = N;
-}
-impl<N, M> CalculateAdd<N> for Succ<M>
-where
-    M: CalculateAdd<N>,
-{
-    type Output = Succ<<M as CalculateAdd<N>>::Output>;
-}
-type Add<A, B> = <A as CalculateAdd<B>>::Output;
-type Double<A> = Add<A, A>;
+use exploring_rust::{
+    binary,
+    boolean::{Bool, If},
+    peano,
+    peano::*,
+};
 
 /// Macro to create a type from a list of `x` and `o` tokens representing the number in binary.
 macro_rules! num {
@@ -51,24 +25,47 @@ macro_rules! num {
 
 fn main() {
     println!("2 + 3 = {}", <Add<num!(x o), num!(x x)> as Value>::VALUE);
+    println!(
+        "6 * 7 = {}",
+        <Mul<num!(x x o), num!(x x x)> as Value>::VALUE
+    );
+    println!(
+        "23 / 5 = {} remainder {}",
+        <Div<num!(x o x x x), num!(x o x)> as Value>::VALUE,
+        <Rem<num!(x o x x x), num!(x o x)> as Value>::VALUE
+    );
+    println!(
+        "0xff - 200 = {}",
+        <Sub<peano!(0xff), peano!(200)> as Value>::VALUE
+    );
+    println!(
+        "max(9, 12) - 5 = {}",
+        <Sub<Max<num!(x o o x), num!(x x o o)>, num!(x o x)> as Value>::VALUE
+    );
+
+    println!(
+        "is 6 * 7 even? {}, is 23 % 5 zero? {}",
+        <IsEven<Mul<peano!(6), peano!(7)>> as Bool>::VALUE,
+        <IsZero<Rem<peano!(23), peano!(5)>> as Bool>::VALUE
+    );
+    println!(
+        "if 9 is even then 1 else 2 = {}",
+        <If<IsEven<peano!(9)>, peano!(1), peano!(2)> as Value>::VALUE
+    );
+
+    // unary numbers this big would need a huge recursion_limit, binary ones only ~20 bits
+    type Thousand = binary!(x x x x x o x o o o);
+    println!(
+        "1000 * 1000 * 1000 = {}",
+        <Mul<Mul<Thousand, Thousand>, Thousand> as Value>::VALUE
+    );
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use std::marker::PhantomData;
 
-    #[test]
-    fn test_add() {
-        type One = Succ<Zero>;
-        type Two = Succ<One>;
-        type Three = Succ<Two>;
-        type Four = Succ<Three>;
-
-        let two_plus_three: usize = <Add<Two, Three> as Value>::VALUE;
-        assert_eq!(two_plus_three, 5);
-        let one_plus_four: usize = <Add<One, Four> as Value>::VALUE;
-        assert_eq!(one_plus_four, 5);
-    }
+    use super::*;
 
     #[test]
     fn test_num_macro() {
@@ -77,4 +74,16 @@ mod tests {
         assert_eq!(<num!(x o x o) as Value>::VALUE, 10);
         assert_eq!(<num!(x x x x x x x x) as Value>::VALUE, 255);
     }
+
+    fn assert_same<A>(_: PhantomData<A>, _: PhantomData<A>) {}
+
+    #[test]
+    fn test_peano_macro() {
+        assert_same(PhantomData::<num!(x o x o)>, PhantomData::<peano!(10)>);
+        assert_same(
+            PhantomData::<num!(x x x x x x x x)>,
+            PhantomData::<peano!(0xff)>,
+        );
+        assert_eq!(<peano!(0o17) as Value>::VALUE, 15);
+    }
 }
@@ -0,0 +1,235 @@
+//! Natural numbers as types in binary, so their size grows logarithmically instead of linearly.
+//!
+//! `B0<N>` and `B1<N>` append a `0` or `1` bit to the number `N` (`2 * N` and `2 * N + 1`) and
+//! `UTerm` is the empty number, so `6 = 0b110` is `B0<B1<B1<UTerm>>>`. Numbers never have leading
+//! zeros (no `B0<UTerm>`) so every number has exactly one type.
+//!
+//! The arithmetic reuses the traits from `peano`, so `Add`, `Mul` and `Value` work for both.
+
+use std::marker::PhantomData;
+
+use crate::{
+    boolean::{False, True},
+    peano::{
+        self, CalculateAdd, CalculateIsEven, CalculateIsZero, CalculateMul, Double, Peano, Succ,
+        ToPeano, Value,
+    },
+};
+
+pub struct UTerm;
+pub struct B0<N>(PhantomData<N>);
+pub struct B1<N>(PhantomData<N>);
+
+impl Value for UTerm {
+    const VALUE: usize = 0;
+}
+impl<N: Value> Value for B0<N> {
+    const VALUE: usize = 2 * N::VALUE;
+}
+impl<N: Value> Value for B1<N> {
+    const VALUE: usize = 2 * N::VALUE + 1;
+}
+
+// Without leading zeros only `UTerm` is zero
+impl CalculateIsZero for UTerm {
+    type Output = True;
+}
+impl<N> CalculateIsZero for B0<N> {
+    type Output = False;
+}
+impl<N> CalculateIsZero for B1<N> {
+    type Output = False;
+}
+
+impl CalculateIsEven for UTerm {
+    type Output = True;
+}
+impl<N> CalculateIsEven for B0<N> {
+    type Output = True;
+}
+impl<N> CalculateIsEven for B1<N> {
+    type Output = False;
+}
+
+pub trait Increment {
+    type Output;
+}
+impl Increment for UTerm {
+    type Output = B1<UTerm>;
+}
+impl<N> Increment for B0<N> {
+    type Output = B1<N>;
+}
+impl<N: Increment> Increment for B1<N> {
+    type Output = B0<Inc<N>>;
+}
+pub type Inc<N> = <N as Increment>::Output;
+
+impl<N> CalculateAdd<N> for UTerm {
+    type Output = N;
+}
+impl<N> CalculateAdd<UTerm> for B0<N> {
+    type Output = B0<N>;
+}
+impl<N> CalculateAdd<UTerm> for B1<N> {
+    type Output = B1<N>;
+}
+impl<N: CalculateAdd<M>, M> CalculateAdd<B0<M>> for B0<N> {
+    type Output = B0<peano::Add<N, M>>;
+}
+impl<N: CalculateAdd<M>, M> CalculateAdd<B1<M>> for B0<N> {
+    type Output = B1<peano::Add<N, M>>;
+}
+impl<N: CalculateAdd<M>, M> CalculateAdd<B0<M>> for B1<N> {
+    type Output = B1<peano::Add<N, M>>;
+}
+impl<N, M> CalculateAdd<B1<M>> for B1<N>
+where
+    N: CalculateAdd<M>,
+    peano::Add<N, M>: Increment,
+{
+    // carry the 1
+    type Output = B0<Inc<peano::Add<N, M>>>;
+}
+
+impl<M> CalculateMul<M> for UTerm {
+    type Output = UTerm;
+}
+/// `2N * M = 2(N * M)`
+impl<N, M> CalculateMul<M> for B0<N>
+where
+    N: CalculateMul<M>,
+    peano::Mul<N, M>: CalculateAdd<peano::Mul<N, M>>,
+{
+    type Output = Double<peano::Mul<N, M>>;
+}
+/// `(2N + 1) * M = 2(N * M) + M`
+impl<N, M> CalculateMul<M> for B1<N>
+where
+    N: CalculateMul<M>,
+    peano::Mul<N, M>: CalculateAdd<peano::Mul<N, M>>,
+    Double<peano::Mul<N, M>>: CalculateAdd<M>,
+{
+    type Output = peano::Add<Double<peano::Mul<N, M>>, M>;
+}
+
+// Conversions to and from the unary `Zero`/`Succ` representation of `peano`
+impl ToPeano for UTerm {
+    type Output = peano::Zero;
+}
+impl<N> ToPeano for B0<N>
+where
+    N: ToPeano,
+    Peano<N>: CalculateAdd<Peano<N>>,
+{
+    type Output = Double<Peano<N>>;
+}
+impl<N> ToPeano for B1<N>
+where
+    N: ToPeano,
+    Peano<N>: CalculateAdd<Peano<N>>,
+{
+    type Output = Succ<Double<Peano<N>>>;
+}
+
+pub trait ToBinary {
+    type Output;
+}
+impl ToBinary for peano::Zero {
+    type Output = UTerm;
+}
+impl<N> ToBinary for Succ<N>
+where
+    N: ToBinary,
+    Binary<N>: Increment,
+{
+    type Output = Inc<Binary<N>>;
+}
+pub type Binary<N> = <N as ToBinary>::Output;
+
+/// Creates a binary number type from a list of `x` and `o` tokens, most significant bit first.
+#[macro_export]
+macro_rules! binary {
+    ($($b:ident) *) => {
+        $crate::binary!($crate::binary::UTerm; $($b) *)
+    };
+    ($prev:ty ; o $($tail:ident) *) => {
+        // `Double` instead of `B0` so leading zeros disappear
+        $crate::binary!($crate::peano::Double<$prev>; $($tail) *)
+    };
+    ($prev:ty ; x $($tail:ident) *) => {
+        $crate::binary!($crate::binary::B1<$prev>; $($tail) *)
+    };
+    ($prev:ty ; ) => {
+        $prev
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        assert_type_eq,
+        peano::{Add, Mul, Zero},
+    };
+
+    use super::*;
+
+    type Three = B1<B1<UTerm>>;
+    type Six = B0<B1<B1<UTerm>>>;
+
+    #[test]
+    fn test_macro() {
+        assert_type_eq::<binary!(), UTerm>();
+        assert_type_eq::<binary!(o o), UTerm>();
+        assert_type_eq::<binary!(o x x o), Six>();
+        assert_eq!(<binary!(x o x o) as Value>::VALUE, 10);
+        assert_eq!(<binary!(x x x x x x x x) as Value>::VALUE, 255);
+    }
+
+    #[test]
+    fn test_add() {
+        assert_type_eq::<Add<Three, Three>, Six>();
+        assert_type_eq::<Add<UTerm, Six>, Six>();
+        assert_type_eq::<Add<Six, UTerm>, Six>();
+        assert_eq!(<Add<binary!(x x x x), binary!(x)> as Value>::VALUE, 16);
+        assert_eq!(<Add<binary!(x o x o), binary!(x x)> as Value>::VALUE, 13);
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_type_eq::<Mul<Three, binary!(x o)>, Six>();
+        assert_type_eq::<Mul<Six, UTerm>, UTerm>();
+        assert_type_eq::<Mul<UTerm, Six>, UTerm>();
+        assert_eq!(<Mul<binary!(x x x), binary!(x o x)> as Value>::VALUE, 35);
+    }
+
+    #[test]
+    fn test_big_numbers() {
+        type Thousand = binary!(x x x x x o x o o o);
+        type Million = Mul<Thousand, Thousand>;
+        assert_eq!(<Million as Value>::VALUE, 1_000_000);
+        assert_eq!(<Mul<Million, Million> as Value>::VALUE, 1_000_000_000_000);
+        assert_eq!(<Add<Million, binary!(x)> as Value>::VALUE, 1_000_001);
+    }
+
+    #[test]
+    fn test_predicates() {
+        use crate::peano::{IsEven, IsZero};
+
+        assert_type_eq::<IsZero<binary!(o o)>, True>();
+        assert_type_eq::<IsZero<Six>, False>();
+        assert_type_eq::<IsEven<Six>, True>();
+        assert_type_eq::<IsEven<Add<Six, Three>>, False>();
+        assert_type_eq::<IsEven<UTerm>, True>();
+    }
+
+    #[test]
+    fn test_peano_conversion() {
+        type PeanoSix = Succ<Succ<Succ<Succ<Succ<Succ<Zero>>>>>>;
+        assert_type_eq::<Peano<Six>, PeanoSix>();
+        assert_type_eq::<Binary<PeanoSix>, Six>();
+        assert_type_eq::<Binary<Zero>, UTerm>();
+        assert_eq!(<Peano<binary!(x x x x x x x)> as Value>::VALUE, 127);
+        assert_type_eq::<Binary<Peano<binary!(x o o x x o x)>>, binary!(x o o x x o x)>();
+    }
+}
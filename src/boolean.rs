@@ -0,0 +1,68 @@
+//! Booleans as types, to branch at the type level.
+//!
+//! Like `peano::Ordering`, the operations are generic associated types of `Bool`, so e.g.
+//! `If<IsZero<N>, A, B>` picks `A` or `B` without any extra trait bounds on them.
+
+pub struct True;
+pub struct False;
+
+pub trait Bool {
+    const VALUE: bool;
+    type Not: Bool;
+    type And<B: Bool>: Bool;
+    type Or<B: Bool>: Bool;
+    /// `Then` if `Self` is `True`, `Else` otherwise.
+    type If<Then, Else>;
+}
+impl Bool for True {
+    const VALUE: bool = true;
+    type Not = False;
+    type And<B: Bool> = B;
+    type Or<B: Bool> = True;
+    type If<Then, Else> = Then;
+}
+impl Bool for False {
+    const VALUE: bool = false;
+    type Not = True;
+    type And<B: Bool> = False;
+    type Or<B: Bool> = B;
+    type If<Then, Else> = Else;
+}
+
+pub type Not<A> = <A as Bool>::Not;
+pub type And<A, B> = <A as Bool>::And<B>;
+pub type Or<A, B> = <A as Bool>::Or<B>;
+pub type If<Cond, Then, Else> = <Cond as Bool>::If<Then, Else>;
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_type_eq;
+
+    use super::*;
+
+    const _: () = assert!(<And<True, Not<False>> as Bool>::VALUE);
+    const _: () = assert!(!<Or<False, And<True, False>> as Bool>::VALUE);
+
+    #[test]
+    fn test_truth_tables() {
+        assert_type_eq::<Not<True>, False>();
+        assert_type_eq::<Not<False>, True>();
+
+        assert_type_eq::<And<True, True>, True>();
+        assert_type_eq::<And<True, False>, False>();
+        assert_type_eq::<And<False, True>, False>();
+        assert_type_eq::<And<False, False>, False>();
+
+        assert_type_eq::<Or<True, True>, True>();
+        assert_type_eq::<Or<True, False>, True>();
+        assert_type_eq::<Or<False, True>, True>();
+        assert_type_eq::<Or<False, False>, False>();
+    }
+
+    #[test]
+    fn test_if() {
+        assert_type_eq::<If<True, u8, String>, u8>();
+        assert_type_eq::<If<False, u8, String>, String>();
+        assert_type_eq::<If<Or<False, True>, If<False, (), u8>, String>, u8>();
+    }
+}
@@ -0,0 +1,223 @@
+//! Signed integers as types, built from the `Zero`/`Succ` naturals of `peano`.
+//!
+//! `PosInt<N>` is `+N` and `NegInt<N>` is `-N` for a natural `N` other than `Zero`, which is
+//! `Z0`, so every integer has exactly one type. Use `Positive<N>`/`Negative<N>` to build them from
+//! any natural or the `int!` macro.
+//!
+//! Like `binary`, the arithmetic implements the traits from `peano` so `Add`, `Sub` and `Mul`
+//! work on integers too.
+
+use std::marker::PhantomData;
+
+use crate::peano::{
+    self, CalculateAdd, CalculateMul, CalculateSub, Cmp, Compare, Equal, Greater, Less, Succ,
+    Value, Zero,
+};
+
+pub struct Z0;
+pub struct PosInt<N>(PhantomData<N>);
+pub struct NegInt<N>(PhantomData<N>);
+
+pub trait Integer {
+    const VALUE: isize;
+}
+impl Integer for Z0 {
+    const VALUE: isize = 0;
+}
+impl<N: Value> Integer for PosInt<N> {
+    const VALUE: isize = N::VALUE as isize;
+}
+impl<N: Value> Integer for NegInt<N> {
+    const VALUE: isize = -(N::VALUE as isize);
+}
+
+/// Conversion of naturals into integers with either sign.
+pub trait Signed {
+    type Positive;
+    type Negative;
+}
+impl Signed for Zero {
+    type Positive = Z0;
+    type Negative = Z0;
+}
+impl<N> Signed for Succ<N> {
+    type Positive = PosInt<Succ<N>>;
+    type Negative = NegInt<Succ<N>>;
+}
+pub type Positive<N> = <N as Signed>::Positive;
+pub type Negative<N> = <N as Signed>::Negative;
+
+pub trait Negation {
+    type Output;
+}
+impl Negation for Z0 {
+    type Output = Z0;
+}
+impl<N> Negation for PosInt<N> {
+    type Output = NegInt<N>;
+}
+impl<N> Negation for NegInt<N> {
+    type Output = PosInt<N>;
+}
+pub type Neg<A> = <A as Negation>::Output;
+
+/// `N - M` for naturals as an integer, `O` is how `N` compares to `M`.
+pub trait NaturalDifference<M, O> {
+    type Output;
+}
+impl<N, M> NaturalDifference<M, Less> for N
+where
+    M: CalculateSub<N>,
+{
+    type Output = NegInt<peano::Sub<M, N>>;
+}
+impl<N, M> NaturalDifference<M, Equal> for N {
+    type Output = Z0;
+}
+impl<N, M> NaturalDifference<M, Greater> for N
+where
+    N: CalculateSub<M>,
+{
+    type Output = PosInt<peano::Sub<N, M>>;
+}
+type Difference<N, M> = <N as NaturalDifference<M, Cmp<N, M>>>::Output;
+
+impl<B> CalculateAdd<B> for Z0 {
+    type Output = B;
+}
+impl<N> CalculateAdd<Z0> for PosInt<N> {
+    type Output = PosInt<N>;
+}
+impl<N> CalculateAdd<Z0> for NegInt<N> {
+    type Output = NegInt<N>;
+}
+impl<N: CalculateAdd<M>, M> CalculateAdd<PosInt<M>> for PosInt<N> {
+    type Output = PosInt<peano::Add<N, M>>;
+}
+impl<N: CalculateAdd<M>, M> CalculateAdd<NegInt<M>> for NegInt<N> {
+    type Output = NegInt<peano::Add<N, M>>;
+}
+impl<N, M> CalculateAdd<NegInt<M>> for PosInt<N>
+where
+    N: Compare<M> + NaturalDifference<M, Cmp<N, M>>,
+{
+    type Output = Difference<N, M>;
+}
+impl<N, M> CalculateAdd<PosInt<M>> for NegInt<N>
+where
+    M: Compare<N> + NaturalDifference<N, Cmp<M, N>>,
+{
+    type Output = Difference<M, N>;
+}
+
+// `A - B = A + (-B)`, unlike for naturals it always exists
+impl<A: CalculateAdd<Z0>> CalculateSub<Z0> for A {
+    type Output = peano::Add<A, Z0>;
+}
+impl<A: CalculateAdd<NegInt<M>>, M> CalculateSub<PosInt<M>> for A {
+    type Output = peano::Add<A, NegInt<M>>;
+}
+impl<A: CalculateAdd<PosInt<M>>, M> CalculateSub<NegInt<M>> for A {
+    type Output = peano::Add<A, PosInt<M>>;
+}
+
+impl<B> CalculateMul<B> for Z0 {
+    type Output = Z0;
+}
+impl<N> CalculateMul<Z0> for PosInt<N> {
+    type Output = Z0;
+}
+impl<N> CalculateMul<Z0> for NegInt<N> {
+    type Output = Z0;
+}
+impl<N: CalculateMul<M>, M> CalculateMul<PosInt<M>> for PosInt<N> {
+    type Output = PosInt<peano::Mul<N, M>>;
+}
+impl<N: CalculateMul<M>, M> CalculateMul<NegInt<M>> for NegInt<N> {
+    type Output = PosInt<peano::Mul<N, M>>;
+}
+impl<N: CalculateMul<M>, M> CalculateMul<NegInt<M>> for PosInt<N> {
+    type Output = NegInt<peano::Mul<N, M>>;
+}
+impl<N: CalculateMul<M>, M> CalculateMul<PosInt<M>> for NegInt<N> {
+    type Output = NegInt<peano::Mul<N, M>>;
+}
+
+/// Creates an integer type from a sign and a list of `x` and `o` tokens for the binary digits,
+/// most significant bit first (`int!(- x o x)` is `-5`), or `int!(0)`.
+#[macro_export]
+macro_rules! int {
+    (0) => {
+        $crate::integer::Z0
+    };
+    (+ $($b:ident) *) => {
+        $crate::integer::Positive<$crate::peano::Peano<$crate::binary!($($b) *)>>
+    };
+    (- $($b:ident) *) => {
+        $crate::integer::Negative<$crate::peano::Peano<$crate::binary!($($b) *)>>
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        assert_type_eq,
+        peano::{Add, Mul, Sub},
+    };
+
+    use super::*;
+
+    type One = Succ<Zero>;
+    type Two = Succ<One>;
+    type Three = Succ<Two>;
+
+    #[test]
+    fn test_macro() {
+        assert_type_eq::<int!(0), Z0>();
+        assert_type_eq::<int!(+ o), Z0>();
+        assert_type_eq::<int!(- o o), Z0>();
+        assert_type_eq::<int!(+ x x), PosInt<Three>>();
+        assert_type_eq::<int!(- x o), NegInt<Two>>();
+        assert_eq!(<int!(- x o x) as Integer>::VALUE, -5);
+        assert_eq!(<int!(+ x x x x) as Integer>::VALUE, 15);
+    }
+
+    #[test]
+    fn test_neg() {
+        assert_type_eq::<Neg<int!(+ x x)>, int!(- x x)>();
+        assert_type_eq::<Neg<int!(- x o)>, int!(+ x o)>();
+        assert_type_eq::<Neg<Z0>, Z0>();
+    }
+
+    #[test]
+    fn test_add() {
+        assert_type_eq::<Add<int!(+ x x), int!(- x x)>, Z0>();
+        assert_type_eq::<Add<int!(- x o), int!(+ x x)>, int!(+ x)>();
+        assert_eq!(<Add<int!(+ x o), int!(- x o x)> as Integer>::VALUE, -3);
+        assert_eq!(<Add<int!(- x o x), int!(+ x o)> as Integer>::VALUE, -3);
+        assert_eq!(<Add<int!(- x o x), int!(- x o)> as Integer>::VALUE, -7);
+        assert_eq!(<Add<int!(+ x o x), int!(+ x o)> as Integer>::VALUE, 7);
+        assert_eq!(<Add<Z0, int!(-x)> as Integer>::VALUE, -1);
+        assert_eq!(<Add<int!(-x), Z0> as Integer>::VALUE, -1);
+    }
+
+    #[test]
+    fn test_sub() {
+        assert_eq!(<Sub<int!(+ x o), int!(+ x o x)> as Integer>::VALUE, -3);
+        assert_eq!(<Sub<int!(- x o), int!(- x o x)> as Integer>::VALUE, 3);
+        assert_eq!(<Sub<int!(- x o), int!(+ x o x)> as Integer>::VALUE, -7);
+        assert_eq!(<Sub<Z0, int!(+ x x)> as Integer>::VALUE, -3);
+        assert_eq!(<Sub<int!(+ x x), Z0> as Integer>::VALUE, 3);
+        assert_type_eq::<Sub<int!(- x x), int!(- x x)>, Z0>();
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(<Mul<int!(+ x o), int!(- x x)> as Integer>::VALUE, -6);
+        assert_eq!(<Mul<int!(- x o), int!(+ x x)> as Integer>::VALUE, -6);
+        assert_eq!(<Mul<int!(- x o), int!(- x x)> as Integer>::VALUE, 6);
+        assert_eq!(<Mul<int!(+ x o), int!(+ x x)> as Integer>::VALUE, 6);
+        assert_type_eq::<Mul<int!(- x o), Z0>, Z0>();
+        assert_type_eq::<Mul<Z0, int!(+ x)>, Z0>();
+    }
+}
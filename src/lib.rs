@@ -0,0 +1,23 @@
+//! Pieces shared between the explorations in `src/bin`.
+
+pub mod binary;
+pub mod boolean;
+pub mod integer;
+pub mod monoid;
+pub mod peano;
+pub mod type_list;
+pub mod units;
+pub mod vect;
+
+pub use exploring_rust_macros::peano;
+
+// Lets `peano!` name this crate as `::exploring_rust` from inside it too
+extern crate self as exploring_rust;
+
+/// Only compiles if `A` and `B` are the same type.
+#[cfg(test)]
+pub(crate) fn assert_type_eq<A, B>()
+where
+    std::marker::PhantomData<A>: Into<std::marker::PhantomData<B>>,
+{
+}
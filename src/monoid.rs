@@ -0,0 +1,1277 @@
+//! Monoids as tag types: `Semigroup`/`Monoid` are implemented on a marker type whose `T` is the
+//! type of the values, so one value type can have many monoids (`Add<usize>`, `Max<usize>`, ...).
+//!
+//! Instances are checked with the property tests in `laws`.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::Hash,
+    rc::Rc,
+};
+
+use num_traits::{One, Zero};
+
+pub mod checkpoint;
+pub mod endomorphism;
+pub mod foldable;
+pub mod laws;
+pub mod measured_tree;
+pub mod range_query;
+pub mod stats;
+pub mod traverse;
+pub mod value;
+pub mod window;
+
+pub trait Semigroup {
+    type T;
+    fn append(a: Self::T, b: Self::T) -> Self::T;
+
+    /// Appends `a` to itself so it's repeated `n` times (`n` must be at least 1).
+    ///
+    /// Uses exponentiation by squaring so it only needs `O(log n)` appends.
+    fn times1(a: Self::T, n: usize) -> Self::T
+    where
+        Self::T: Clone,
+    {
+        assert!(n > 0, "times1 needs n >= 1");
+        let mut base = a;
+        let mut n = n;
+        while n & 1 == 0 {
+            base = Self::append(base.clone(), base);
+            n >>= 1;
+        }
+        let mut res = base.clone();
+        n >>= 1;
+        while n > 0 {
+            base = Self::append(base.clone(), base);
+            if n & 1 == 1 {
+                res = Self::append(res, base.clone());
+            }
+            n >>= 1;
+        }
+        res
+    }
+}
+
+pub trait Monoid: Semigroup {
+    fn identity() -> Self::T;
+
+    fn times(a: Self::T, n: usize) -> Self::T
+    where
+        Self::T: Clone,
+    {
+        if n == 0 {
+            Self::identity()
+        } else {
+            Self::times1(a, n)
+        }
+    }
+
+    fn accumulate<I>(iter: I) -> Self::T
+    where
+        I: IntoIterator<Item = Self::T>,
+    {
+        iter.into_iter().fold(Self::identity(), Self::append)
+    }
+
+    /// Same as `accumulate` but splits the input into contiguous chunks, accumulates them on
+    /// separate threads and combines the partial results in order (so `append` only has to be
+    /// associative, not commutative).
+    #[cfg(not(feature = "rayon"))]
+    fn par_accumulate<I>(iter: I) -> Self::T
+    where
+        I: IntoIterator<Item = Self::T>,
+        Self::T: Send,
+    {
+        use std::{num::NonZeroUsize, thread};
+
+        let mut items = iter.into_iter().collect::<Vec<_>>().into_iter();
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let chunk_size = items.len().div_ceil(threads).max(1);
+        let mut chunks = Vec::with_capacity(threads);
+        while items.len() > 0 {
+            chunks.push(items.by_ref().take(chunk_size).collect::<Vec<_>>());
+        }
+        thread::scope(|s| {
+            let handles = chunks
+                .into_iter()
+                .map(|chunk| s.spawn(move || Self::accumulate(chunk)))
+                .collect::<Vec<_>>();
+            Self::accumulate(handles.into_iter().map(|h| h.join().unwrap()))
+        })
+    }
+
+    /// Same as `accumulate` but splits the input into contiguous chunks, accumulates them on
+    /// separate threads and combines the partial results in order (so `append` only has to be
+    /// associative, not commutative).
+    #[cfg(feature = "rayon")]
+    fn par_accumulate<I>(iter: I) -> Self::T
+    where
+        I: IntoIterator<Item = Self::T>,
+        Self::T: Send,
+    {
+        use rayon::prelude::*;
+
+        // rayon's fold/reduce keep the relative order of the items
+        iter.into_iter()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .fold(Self::identity, Self::append)
+            .reduce(Self::identity, Self::append)
+    }
+
+    /// Accumulates the slice as a balanced tree of `append`s instead of a left fold.
+    ///
+    /// Every item takes part in `O(log n)` appends, which keeps the rounding error low for floats
+    /// and avoids the quadratic copying of repeatedly appending to a growing string.
+    fn accumulate_balanced(items: &[Self::T]) -> Self::T
+    where
+        Self::T: Clone,
+    {
+        match items {
+            [] => Self::identity(),
+            [a] => a.clone(),
+            _ => {
+                let (left, right) = items.split_at(items.len() / 2);
+                Self::append(
+                    Self::accumulate_balanced(left),
+                    Self::accumulate_balanced(right),
+                )
+            }
+        }
+    }
+}
+
+/// Monoid where the order of the arguments to `append` doesn't matter.
+pub trait CommutativeMonoid: Monoid {}
+
+/// Monoid where every element has an inverse, so `append(a, inverse(a)) == identity()`.
+pub trait Group: Monoid {
+    fn inverse(a: Self::T) -> Self::T;
+}
+
+/// Plain `+`, for floats it's only approximately lawful (rounding depends on the order) and for
+/// integers an overflow panics in debug builds and wraps in release ones, pick one of
+/// `WrappingAdd`, `SaturatingAdd` or `CheckedAdd` when that's a concern.
+pub struct Add<T = usize>(std::marker::PhantomData<T>);
+impl<T: Zero> Semigroup for Add<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        a + b
+    }
+}
+impl<T: Zero> Monoid for Add<T> {
+    fn identity() -> T {
+        T::zero()
+    }
+}
+impl<T: Zero> CommutativeMonoid for Add<T> {}
+
+/// Plain `*`, with the same caveats as `Add`.
+pub struct Mul<T = usize>(std::marker::PhantomData<T>);
+impl<T: One> Semigroup for Mul<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        a * b
+    }
+}
+impl<T: One> Monoid for Mul<T> {
+    fn identity() -> T {
+        T::one()
+    }
+}
+impl<T: One> CommutativeMonoid for Mul<T> {}
+
+/// Types with a smallest and a largest value, used as the identities of `Max` and `Min`.
+///
+/// Other `Ord` types still get a monoid by lifting the semigroup: `Option<Max<T>>`.
+pub trait Bounded {
+    const MIN: Self;
+    const MAX: Self;
+}
+macro_rules! impl_bounded {
+    ($($t:ty) *) => {
+        $(
+            impl Bounded for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+            }
+        )*
+    };
+}
+impl_bounded!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize char);
+impl Bounded for bool {
+    const MIN: Self = false;
+    const MAX: Self = true;
+}
+impl Bounded for f32 {
+    const MIN: Self = f32::NEG_INFINITY;
+    const MAX: Self = f32::INFINITY;
+}
+impl Bounded for f64 {
+    const MIN: Self = f64::NEG_INFINITY;
+    const MAX: Self = f64::INFINITY;
+}
+
+/// Works for anything `PartialOrd` so floats can be used too, but then it's only lawful
+/// without NaNs.
+pub struct Max<T = usize>(std::marker::PhantomData<T>);
+impl<T: PartialOrd> Semigroup for Max<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        if b > a {
+            b
+        } else {
+            a
+        }
+    }
+}
+impl<T: PartialOrd + Bounded> Monoid for Max<T> {
+    fn identity() -> T {
+        T::MIN
+    }
+}
+impl<T: PartialOrd + Bounded> CommutativeMonoid for Max<T> {}
+
+/// Works for anything `PartialOrd` so floats can be used too, but then it's only lawful
+/// without NaNs.
+pub struct Min<T = usize>(std::marker::PhantomData<T>);
+impl<T: PartialOrd> Semigroup for Min<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        if b < a {
+            b
+        } else {
+            a
+        }
+    }
+}
+impl<T: PartialOrd + Bounded> Monoid for Min<T> {
+    fn identity() -> T {
+        T::MAX
+    }
+}
+impl<T: PartialOrd + Bounded> CommutativeMonoid for Min<T> {}
+
+/// Addition modulo `2^bits`, a group for both signed and unsigned integers.
+pub struct WrappingAdd<T = usize>(std::marker::PhantomData<T>);
+impl<T: Zero + num_traits::WrappingAdd> Semigroup for WrappingAdd<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        a.wrapping_add(&b)
+    }
+}
+impl<T: Zero + num_traits::WrappingAdd> Monoid for WrappingAdd<T> {
+    fn identity() -> T {
+        T::zero()
+    }
+}
+impl<T: Zero + num_traits::WrappingAdd> CommutativeMonoid for WrappingAdd<T> {}
+impl<T: Zero + num_traits::WrappingAdd + num_traits::WrappingNeg> Group for WrappingAdd<T> {
+    fn inverse(a: T) -> T {
+        a.wrapping_neg()
+    }
+}
+
+pub struct WrappingMul<T = usize>(std::marker::PhantomData<T>);
+impl<T: One + num_traits::WrappingMul> Semigroup for WrappingMul<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        a.wrapping_mul(&b)
+    }
+}
+impl<T: One + num_traits::WrappingMul> Monoid for WrappingMul<T> {
+    fn identity() -> T {
+        T::one()
+    }
+}
+impl<T: One + num_traits::WrappingMul> CommutativeMonoid for WrappingMul<T> {}
+
+/// Addition clamped to the range of `T`.
+///
+/// Only lawful for unsigned numbers: with signed ones `(MAX + 1) + -1` is `MAX - 1` while
+/// `MAX + (1 + -1)` is `MAX`.
+pub struct SaturatingAdd<T = usize>(std::marker::PhantomData<T>);
+impl<T: Zero + num_traits::SaturatingAdd> Semigroup for SaturatingAdd<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        a.saturating_add(&b)
+    }
+}
+impl<T: Zero + num_traits::SaturatingAdd> Monoid for SaturatingAdd<T> {
+    fn identity() -> T {
+        T::zero()
+    }
+}
+impl<T: Zero + num_traits::SaturatingAdd> CommutativeMonoid for SaturatingAdd<T> {}
+
+/// Multiplication clamped to the range of `T`, only lawful for unsigned numbers (like
+/// `SaturatingAdd`).
+pub struct SaturatingMul<T = usize>(std::marker::PhantomData<T>);
+impl<T: One + num_traits::SaturatingMul> Semigroup for SaturatingMul<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        a.saturating_mul(&b)
+    }
+}
+impl<T: One + num_traits::SaturatingMul> Monoid for SaturatingMul<T> {
+    fn identity() -> T {
+        T::one()
+    }
+}
+impl<T: One + num_traits::SaturatingMul> CommutativeMonoid for SaturatingMul<T> {}
+
+/// Addition where an overflow anywhere makes the whole result `None`.
+///
+/// Only lawful for unsigned numbers: with signed ones `(MAX + 1) + -1` overflows while
+/// `MAX + (1 + -1)` doesn't. Not a group either as there's no way back from `None`.
+pub struct CheckedAdd<T = usize>(std::marker::PhantomData<T>);
+impl<T: Zero + num_traits::CheckedAdd> Semigroup for CheckedAdd<T> {
+    type T = Option<T>;
+
+    fn append(a: Option<T>, b: Option<T>) -> Option<T> {
+        a?.checked_add(&b?)
+    }
+}
+impl<T: Zero + num_traits::CheckedAdd> Monoid for CheckedAdd<T> {
+    fn identity() -> Option<T> {
+        Some(T::zero())
+    }
+
+    /// Stops at the first overflow.
+    fn accumulate<I>(iter: I) -> Option<T>
+    where
+        I: IntoIterator<Item = Option<T>>,
+    {
+        iter.into_iter()
+            .try_fold(T::zero(), |acc, x| acc.checked_add(&x?))
+    }
+}
+impl<T: Zero + num_traits::CheckedAdd> CommutativeMonoid for CheckedAdd<T> {}
+
+/// Multiplication where an overflow anywhere makes the whole result `None`.
+///
+/// Only lawful for unsigned numbers without zeros: `(MAX * 2) * 0` overflows while
+/// `MAX * (2 * 0)` is `0`.
+pub struct CheckedMul<T = usize>(std::marker::PhantomData<T>);
+impl<T: One + num_traits::CheckedMul> Semigroup for CheckedMul<T> {
+    type T = Option<T>;
+
+    fn append(a: Option<T>, b: Option<T>) -> Option<T> {
+        a?.checked_mul(&b?)
+    }
+}
+impl<T: One + num_traits::CheckedMul> Monoid for CheckedMul<T> {
+    fn identity() -> Option<T> {
+        Some(T::one())
+    }
+
+    /// Stops at the first overflow.
+    fn accumulate<I>(iter: I) -> Option<T>
+    where
+        I: IntoIterator<Item = Option<T>>,
+    {
+        iter.into_iter()
+            .try_fold(T::one(), |acc, x| acc.checked_mul(&x?))
+    }
+}
+impl<T: One + num_traits::CheckedMul> CommutativeMonoid for CheckedMul<T> {}
+
+/// Bitwise exclusive or, a group where every value is its own inverse.
+pub struct Xor<T = usize>(std::marker::PhantomData<T>);
+impl<T: num_traits::PrimInt> Semigroup for Xor<T> {
+    type T = T;
+
+    fn append(a: T, b: T) -> T {
+        a ^ b
+    }
+}
+impl<T: num_traits::PrimInt> Monoid for Xor<T> {
+    fn identity() -> T {
+        T::zero()
+    }
+}
+impl<T: num_traits::PrimInt> CommutativeMonoid for Xor<T> {}
+impl<T: num_traits::PrimInt> Group for Xor<T> {
+    fn inverse(a: T) -> T {
+        a
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VecAppend<T>(std::marker::PhantomData<T>);
+impl<T> Semigroup for VecAppend<T> {
+    type T = Vec<T>;
+
+    fn append(mut a: Vec<T>, mut b: Vec<T>) -> Vec<T> {
+        a.append(&mut b);
+        a
+    }
+}
+impl<T> Monoid for VecAppend<T> {
+    fn identity() -> Vec<T> {
+        Vec::new()
+    }
+}
+
+/// Merges maps, values under the same key are combined with `M`.
+pub struct MapMerge<K, M>(std::marker::PhantomData<(K, M)>);
+impl<K: Eq + Hash, M: Semigroup> Semigroup for MapMerge<K, M> {
+    type T = HashMap<K, M::T>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        merge_maps(a, b, M::append)
+    }
+}
+impl<K: Eq + Hash, M: Semigroup> Monoid for MapMerge<K, M> {
+    fn identity() -> Self::T {
+        HashMap::new()
+    }
+}
+impl<K: Eq + Hash, M: CommutativeMonoid> CommutativeMonoid for MapMerge<K, M> {}
+
+/// Same as `MapMerge` but for `BTreeMap`s.
+pub struct BTreeMapMerge<K, M>(std::marker::PhantomData<(K, M)>);
+impl<K: Ord, M: Semigroup> Semigroup for BTreeMapMerge<K, M> {
+    type T = BTreeMap<K, M::T>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        merge_maps(a, b, M::append)
+    }
+}
+impl<K: Ord, M: Semigroup> Monoid for BTreeMapMerge<K, M> {
+    fn identity() -> Self::T {
+        BTreeMap::new()
+    }
+}
+impl<K: Ord, M: CommutativeMonoid> CommutativeMonoid for BTreeMapMerge<K, M> {}
+
+/// The operations `merge_maps` needs from `HashMap` and `BTreeMap`.
+trait MergeMap<K, V>: IntoIterator<Item = (K, V)> {
+    fn len(&self) -> usize;
+
+    /// Inserts `v`, or `f(existing, v)` if there already is a value under `k`.
+    fn merge_value<F: FnOnce(V, V) -> V>(&mut self, k: K, v: V, f: F);
+}
+impl<K: Eq + Hash, V> MergeMap<K, V> for HashMap<K, V> {
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn merge_value<F: FnOnce(V, V) -> V>(&mut self, k: K, v: V, f: F) {
+        use std::collections::hash_map::Entry;
+
+        match self.entry(k) {
+            Entry::Vacant(entry) => {
+                entry.insert(v);
+            }
+            // `f` needs the old value by value, so it has to be taken out of the map
+            Entry::Occupied(entry) => {
+                let (k, old) = entry.remove_entry();
+                self.insert(k, f(old, v));
+            }
+        }
+    }
+}
+impl<K: Ord, V> MergeMap<K, V> for BTreeMap<K, V> {
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn merge_value<F: FnOnce(V, V) -> V>(&mut self, k: K, v: V, f: F) {
+        use std::collections::btree_map::Entry;
+
+        match self.entry(k) {
+            Entry::Vacant(entry) => {
+                entry.insert(v);
+            }
+            Entry::Occupied(entry) => {
+                let (k, old) = entry.remove_entry();
+                self.insert(k, f(old, v));
+            }
+        }
+    }
+}
+
+fn merge_maps<Map, K, V>(mut a: Map, mut b: Map, append: fn(V, V) -> V) -> Map
+where
+    Map: MergeMap<K, V>,
+{
+    // move the smaller map into the bigger one but keep the order of the values for `append`
+    if a.len() >= b.len() {
+        for (k, v) in b {
+            a.merge_value(k, v, append);
+        }
+        a
+    } else {
+        for (k, v) in a {
+            b.merge_value(k, v, |y, v| append(v, y));
+        }
+        b
+    }
+}
+
+pub struct SetUnion<T>(std::marker::PhantomData<T>);
+impl<T: Eq + Hash> Semigroup for SetUnion<T> {
+    type T = HashSet<T>;
+
+    fn append(mut a: HashSet<T>, mut b: HashSet<T>) -> HashSet<T> {
+        if a.len() < b.len() {
+            std::mem::swap(&mut a, &mut b);
+        }
+        a.extend(b);
+        a
+    }
+}
+impl<T: Eq + Hash> Monoid for SetUnion<T> {
+    fn identity() -> HashSet<T> {
+        HashSet::new()
+    }
+}
+impl<T: Eq + Hash> CommutativeMonoid for SetUnion<T> {}
+
+/// Only a semigroup as the identity would have to be the set of all values,
+/// use `Option<SetIntersection<T>>` when a monoid is needed.
+pub struct SetIntersection<T>(std::marker::PhantomData<T>);
+impl<T: Eq + Hash> Semigroup for SetIntersection<T> {
+    type T = HashSet<T>;
+
+    fn append(mut a: HashSet<T>, b: HashSet<T>) -> HashSet<T> {
+        a.retain(|x| b.contains(x));
+        a
+    }
+}
+
+pub struct StringAppend;
+impl Semigroup for StringAppend {
+    type T = String;
+
+    fn append(mut a: String, b: String) -> String {
+        a.push_str(&b);
+        a
+    }
+}
+impl Monoid for StringAppend {
+    fn identity() -> String {
+        String::new()
+    }
+}
+
+impl Semigroup for () {
+    type T = ();
+
+    #[allow(clippy::unused_unit)]
+    fn append((): Self::T, (): Self::T) -> Self::T {
+        ()
+    }
+}
+impl Monoid for () {
+    #[allow(clippy::unused_unit)]
+    fn identity() -> Self::T {
+        ()
+    }
+}
+impl CommutativeMonoid for () {}
+impl Group for () {
+    #[allow(clippy::unused_unit)]
+    fn inverse((): Self::T) -> Self::T {
+        ()
+    }
+}
+
+impl<A: Semigroup> Semigroup for (A,) {
+    type T = (A::T,);
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        (A::append(a.0, b.0),)
+    }
+}
+impl<A: Monoid> Monoid for (A,) {
+    fn identity() -> Self::T {
+        (A::identity(),)
+    }
+}
+impl<A: CommutativeMonoid> CommutativeMonoid for (A,) {}
+impl<A: Group> Group for (A,) {
+    fn inverse(a: Self::T) -> Self::T {
+        (A::inverse(a.0),)
+    }
+}
+
+impl<A, B> Semigroup for (A, B)
+where
+    A: Semigroup,
+    B: Semigroup,
+{
+    type T = (A::T, B::T);
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        (A::append(a.0, b.0), B::append(a.1, b.1))
+    }
+}
+impl<A: Monoid, B: Monoid> Monoid for (A, B) {
+    fn identity() -> Self::T {
+        (A::identity(), B::identity())
+    }
+}
+impl<A: CommutativeMonoid, B: CommutativeMonoid> CommutativeMonoid for (A, B) {}
+impl<A: Group, B: Group> Group for (A, B) {
+    fn inverse(a: Self::T) -> Self::T {
+        (A::inverse(a.0), B::inverse(a.1))
+    }
+}
+
+impl<A, B, C> Semigroup for (A, B, C)
+where
+    A: Semigroup,
+    B: Semigroup,
+    C: Semigroup,
+{
+    type T = (A::T, B::T, C::T);
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        (
+            A::append(a.0, b.0),
+            B::append(a.1, b.1),
+            C::append(a.2, b.2),
+        )
+    }
+}
+impl<A, B, C> Monoid for (A, B, C)
+where
+    A: Monoid,
+    B: Monoid,
+    C: Monoid,
+{
+    fn identity() -> Self::T {
+        (A::identity(), B::identity(), C::identity())
+    }
+}
+impl<A, B, C> CommutativeMonoid for (A, B, C)
+where
+    A: CommutativeMonoid,
+    B: CommutativeMonoid,
+    C: CommutativeMonoid,
+{
+}
+impl<A, B, C> Group for (A, B, C)
+where
+    A: Group,
+    B: Group,
+    C: Group,
+{
+    fn inverse(a: Self::T) -> Self::T {
+        (A::inverse(a.0), B::inverse(a.1), C::inverse(a.2))
+    }
+}
+
+pub type Fun<Args, Return> = Rc<dyn Fn(Args) -> Return>;
+
+pub struct TraverseFn<A, B>(std::marker::PhantomData<(A, B)>);
+impl<A, B> Semigroup for TraverseFn<A, B>
+where
+    A: Clone + 'static,
+    B: Monoid,
+    B::T: 'static,
+{
+    type T = Fun<A, B::T>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        Rc::new(move |x| B::append(a(x.clone()), b(x)))
+    }
+}
+impl<A, B> Monoid for TraverseFn<A, B>
+where
+    A: Clone + 'static,
+    B: Monoid,
+    B::T: 'static,
+{
+    fn identity() -> Self::T {
+        Rc::new(|_| B::identity())
+    }
+}
+impl<A, B> CommutativeMonoid for TraverseFn<A, B>
+where
+    A: Clone + 'static,
+    B: CommutativeMonoid,
+    B::T: 'static,
+{
+}
+impl<A, B> Group for TraverseFn<A, B>
+where
+    A: Clone + 'static,
+    B: Group,
+    B::T: 'static,
+{
+    fn inverse(a: Self::T) -> Self::T {
+        Rc::new(move |x| B::inverse(a(x)))
+    }
+}
+
+pub type Endomorphism<A> = Fun<A, A>;
+
+pub struct ComposeEndomorphism<A>(std::marker::PhantomData<A>);
+impl<A> Semigroup for ComposeEndomorphism<A>
+where
+    A: 'static,
+{
+    type T = Endomorphism<A>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        Rc::new(move |x| a(b(x)))
+    }
+}
+impl<A> Monoid for ComposeEndomorphism<A>
+where
+    A: 'static,
+{
+    fn identity() -> Self::T {
+        Rc::new(|x| x)
+    }
+}
+
+/// Function together with its inverse.
+pub struct Bijection<A> {
+    pub forward: Endomorphism<A>,
+    pub backward: Endomorphism<A>,
+}
+impl<A> Clone for Bijection<A> {
+    fn clone(&self) -> Self {
+        Self {
+            forward: self.forward.clone(),
+            backward: self.backward.clone(),
+        }
+    }
+}
+
+pub struct ComposeBijection<A>(std::marker::PhantomData<A>);
+impl<A> Semigroup for ComposeBijection<A>
+where
+    A: 'static,
+{
+    type T = Bijection<A>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        Bijection {
+            forward: ComposeEndomorphism::append(a.forward, b.forward),
+            backward: ComposeEndomorphism::append(b.backward, a.backward),
+        }
+    }
+}
+impl<A> Monoid for ComposeBijection<A>
+where
+    A: 'static,
+{
+    fn identity() -> Self::T {
+        Bijection {
+            forward: ComposeEndomorphism::identity(),
+            backward: ComposeEndomorphism::identity(),
+        }
+    }
+}
+impl<A> Group for ComposeBijection<A>
+where
+    A: 'static,
+{
+    fn inverse(a: Self::T) -> Self::T {
+        Bijection {
+            forward: a.backward,
+            backward: a.forward,
+        }
+    }
+}
+
+impl<A: Semigroup> Semigroup for Option<A> {
+    type T = Option<A::T>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(A::append(a, b)),
+            (Some(a), None) | (None, Some(a)) => Some(a),
+            (None, None) => None,
+        }
+    }
+}
+impl<A: Semigroup> Monoid for Option<A> {
+    fn identity() -> Self::T {
+        None
+    }
+}
+impl<A: CommutativeMonoid> CommutativeMonoid for Option<A> {}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::laws::*;
+    use super::*;
+
+    macro_rules! int_tests {
+        ($($name:ident: $t:ty,)*) => {
+            $(
+                mod $name {
+                    use quickcheck::TestResult;
+                    use quickcheck_macros::quickcheck;
+
+                    use super::super::laws::*;
+                    use super::super::*;
+
+                    #[quickcheck]
+                    fn test_add(a: $t, b: $t, c: $t) {
+                        // small enough to never overflow
+                        let (a, b, c) = (a / 4, b / 4, c / 4);
+                        check_identity::<Add<$t>>(a);
+                        check_associative::<Add<$t>>(a, b, c);
+                        check_commutative::<Add<$t>>(a, b);
+                    }
+
+                    #[quickcheck]
+                    fn test_mul(a: $t, b: $t, c: $t) -> TestResult {
+                        let (a, b, c) = (a % 8, b % 8, c % 8);
+                        if a.checked_mul(b).and_then(|x| x.checked_mul(c)).is_none() {
+                            return TestResult::discard();
+                        }
+                        check_identity::<Mul<$t>>(a);
+                        check_associative::<Mul<$t>>(a, b, c);
+                        check_commutative::<Mul<$t>>(a, b);
+                        TestResult::passed()
+                    }
+
+                    #[quickcheck]
+                    fn test_max_min(a: $t, b: $t, c: $t) {
+                        check_identity::<Max<$t>>(a);
+                        check_associative::<Max<$t>>(a, b, c);
+                        check_commutative::<Max<$t>>(a, b);
+                        check_identity::<Min<$t>>(a);
+                        check_associative::<Min<$t>>(a, b, c);
+                        check_commutative::<Min<$t>>(a, b);
+                    }
+
+                    #[quickcheck]
+                    fn test_wrapping(a: $t, b: $t, c: $t) {
+                        check_identity::<WrappingAdd<$t>>(a);
+                        check_associative::<WrappingAdd<$t>>(a, b, c);
+                        check_commutative::<WrappingAdd<$t>>(a, b);
+                        check_inverse::<WrappingAdd<$t>>(a);
+                        check_identity::<WrappingMul<$t>>(a);
+                        check_associative::<WrappingMul<$t>>(a, b, c);
+                        check_commutative::<WrappingMul<$t>>(a, b);
+                    }
+
+                    #[quickcheck]
+                    fn test_xor(a: $t, b: $t, c: $t) {
+                        check_identity::<Xor<$t>>(a);
+                        check_associative::<Xor<$t>>(a, b, c);
+                        check_commutative::<Xor<$t>>(a, b);
+                        check_inverse::<Xor<$t>>(a);
+                    }
+                }
+            )*
+        };
+    }
+    int_tests! {
+        u8_tests: u8,
+        u16_tests: u16,
+        u32_tests: u32,
+        u64_tests: u64,
+        u128_tests: u128,
+        usize_tests: usize,
+        i8_tests: i8,
+        i16_tests: i16,
+        i32_tests: i32,
+        i64_tests: i64,
+        i128_tests: i128,
+        isize_tests: isize,
+    }
+
+    /// Saturating and checked arithmetic are only lawful for unsigned numbers.
+    macro_rules! unsigned_tests {
+        ($($name:ident: $t:ty,)*) => {
+            $(
+                mod $name {
+                    use std::num::NonZero;
+
+                    use quickcheck_macros::quickcheck;
+
+                    use super::super::laws::*;
+                    use super::super::*;
+
+                    #[quickcheck]
+                    fn test_saturating(a: $t, b: $t, c: $t) {
+                        check_identity::<SaturatingAdd<$t>>(a);
+                        check_associative::<SaturatingAdd<$t>>(a, b, c);
+                        check_commutative::<SaturatingAdd<$t>>(a, b);
+                        check_identity::<SaturatingMul<$t>>(a);
+                        check_associative::<SaturatingMul<$t>>(a, b, c);
+                        check_commutative::<SaturatingMul<$t>>(a, b);
+                    }
+
+                    #[quickcheck]
+                    fn test_checked(a: Option<$t>, b: Option<$t>, c: Option<$t>) {
+                        check_identity::<CheckedAdd<$t>>(a);
+                        check_associative::<CheckedAdd<$t>>(a, b, c);
+                        check_commutative::<CheckedAdd<$t>>(a, b);
+                    }
+
+                    #[quickcheck]
+                    fn test_checked_mul(a: NonZero<$t>, b: NonZero<$t>, c: NonZero<$t>) {
+                        let (a, b, c) = (Some(a.get()), Some(b.get()), Some(c.get()));
+                        check_identity::<CheckedMul<$t>>(a);
+                        check_associative::<CheckedMul<$t>>(a, b, c);
+                        check_commutative::<CheckedMul<$t>>(a, b);
+                    }
+                }
+            )*
+        };
+    }
+    unsigned_tests! {
+        u8_unsigned_tests: u8,
+        u16_unsigned_tests: u16,
+        u32_unsigned_tests: u32,
+        u64_unsigned_tests: u64,
+        u128_unsigned_tests: u128,
+        usize_unsigned_tests: usize,
+    }
+
+    /// Floats are only approximately lawful, rounding depends on the order of operations.
+    macro_rules! float_tests {
+        ($($name:ident: $t:ty,)*) => {
+            $(
+                mod $name {
+                    use quickcheck_macros::quickcheck;
+
+                    use super::super::laws::*;
+                    use super::super::*;
+
+                    #[quickcheck]
+                    fn test_float(a: i16, b: i16, c: i16) {
+                        let (a, b, c) =
+                            (<$t>::from(a) / 7.0, <$t>::from(b) / 7.0, <$t>::from(c) / 7.0);
+                        check_identity::<Add<$t>>(a);
+                        check_associative_approx::<Add<$t>>(a, b, c);
+                        check_commutative::<Add<$t>>(a, b);
+                        check_identity::<Mul<$t>>(a);
+                        check_associative_approx::<Mul<$t>>(a, b, c);
+                        check_commutative::<Mul<$t>>(a, b);
+                        check_identity::<Max<$t>>(a);
+                        check_associative::<Max<$t>>(a, b, c);
+                        check_identity::<Min<$t>>(a);
+                        check_associative::<Min<$t>>(a, b, c);
+                    }
+                }
+            )*
+        };
+    }
+    float_tests! {
+        f32_tests: f32,
+        f64_tests: f64,
+    }
+
+    #[test]
+    fn test_checked_overflow() {
+        assert_eq!(CheckedAdd::<u8>::accumulate([Some(200), Some(100)]), None);
+        assert_eq!(CheckedMul::<u8>::accumulate([Some(16), Some(16)]), None);
+        assert_eq!(
+            CheckedMul::<u8>::accumulate([Some(15), Some(17)]),
+            Some(255)
+        );
+        assert_eq!(SaturatingAdd::<u8>::accumulate([200, 100]), 255);
+        assert_eq!(WrappingAdd::<u8>::accumulate([200, 100]), 44);
+    }
+
+    #[quickcheck]
+    fn test_vec_append(a: Vec<usize>, b: Vec<usize>, c: Vec<usize>) {
+        check_identity::<VecAppend<usize>>(a.clone());
+        check_associative::<VecAppend<usize>>(a, b, c);
+    }
+
+    #[quickcheck]
+    fn test_map_merge(
+        a: HashMap<u8, Vec<usize>>,
+        b: HashMap<u8, Vec<usize>>,
+        c: HashMap<u8, Vec<usize>>,
+    ) {
+        check_identity::<MapMerge<u8, VecAppend<usize>>>(a.clone());
+        check_associative::<MapMerge<u8, VecAppend<usize>>>(a, b, c);
+    }
+
+    #[quickcheck]
+    fn test_map_merge_commutative(a: HashMap<u8, usize>, b: HashMap<u8, usize>) {
+        check_commutative::<MapMerge<u8, SaturatingAdd>>(a.clone(), b.clone());
+        let merged = MapMerge::<u8, SaturatingAdd>::append(a.clone(), b.clone());
+        for (k, v) in merged {
+            let expected = a
+                .get(&k)
+                .copied()
+                .unwrap_or(0)
+                .saturating_add(b.get(&k).copied().unwrap_or(0));
+            assert_eq!(v, expected);
+        }
+    }
+
+    #[quickcheck]
+    fn test_btree_map_merge(
+        a: BTreeMap<u8, String>,
+        b: BTreeMap<u8, String>,
+        c: BTreeMap<u8, String>,
+    ) {
+        check_identity::<BTreeMapMerge<u8, StringAppend>>(a.clone());
+        check_associative::<BTreeMapMerge<u8, StringAppend>>(a, b, c);
+    }
+
+    #[quickcheck]
+    fn test_set_union(a: HashSet<u8>, b: HashSet<u8>, c: HashSet<u8>) {
+        check_identity::<SetUnion<u8>>(a.clone());
+        check_associative::<SetUnion<u8>>(a.clone(), b.clone(), c);
+        check_commutative::<SetUnion<u8>>(a, b);
+    }
+
+    #[quickcheck]
+    fn test_set_intersection(a: HashSet<u8>, b: HashSet<u8>, c: HashSet<u8>) {
+        check_associative::<SetIntersection<u8>>(a.clone(), b.clone(), c.clone());
+        check_identity::<Option<SetIntersection<u8>>>(Some(a.clone()));
+        check_associative::<Option<SetIntersection<u8>>>(Some(a), Some(b), Some(c));
+    }
+
+    #[quickcheck]
+    fn test_string_append(a: String, b: String, c: String) {
+        check_identity::<StringAppend>(a.clone());
+        check_associative::<StringAppend>(a, b, c);
+    }
+
+    #[test]
+    fn test_unit() {
+        check_identity::<()>(());
+        check_associative::<()>((), (), ());
+    }
+
+    #[quickcheck]
+    fn test_tuple(a: usize, b: usize, c: usize, d: usize, e: usize, f: usize) {
+        check_identity::<(SaturatingAdd, SaturatingMul)>((a, b));
+        check_associative::<(SaturatingAdd, SaturatingMul)>((a, b), (c, d), (e, f));
+    }
+
+    #[quickcheck]
+    fn test_tuple3(a: usize, b: usize, c: usize, x: Vec<usize>, y: Vec<usize>, z: Vec<usize>) {
+        check_identity::<(SaturatingAdd, SaturatingMul, VecAppend<usize>)>((a, a, x.clone()));
+        check_associative::<(SaturatingAdd, SaturatingMul, VecAppend<usize>)>(
+            (a, a, x.clone()),
+            (b, b, y.clone()),
+            (c, c, z.clone()),
+        );
+    }
+
+    #[test]
+    fn test_traverse_fn() {
+        type M = TraverseFn<usize, SaturatingAdd>;
+        // saturating so we don't have to worry about overflows
+        let a = Rc::new(|x: usize| x.saturating_add(1));
+        let b = Rc::new(|x: usize| x.saturating_mul(5));
+        let c = Rc::new(|x: usize| x.saturating_mul(x));
+
+        // checking by hand because we can't really check functions for equality
+        let x = M::append(a.clone(), M::identity());
+        let y = M::append(M::identity(), a.clone());
+        check_fun_equality(x, y);
+
+        // checking by hand because we can't really check functions for equality
+        let x = M::append(M::append(a.clone(), b.clone()), c.clone());
+        let y = M::append(a.clone(), M::append(b.clone(), c.clone()));
+        check_fun_equality(x, y)
+    }
+
+    #[test]
+    fn test_compose_endo() {
+        type M = ComposeEndomorphism<usize>;
+        // saturating so we don't have to worry about overflows
+        let a = Rc::new(|x: usize| x.saturating_add(1));
+        let b = Rc::new(|x: usize| x.saturating_mul(5));
+        let c = Rc::new(|x: usize| x.saturating_mul(x));
+
+        // checking by hand because we can't really check functions for equality
+        let x = M::append(a.clone(), M::identity());
+        let y = M::append(M::identity(), a.clone());
+        check_fun_equality(x, y);
+
+        // checking by hand because we can't really check functions for equality
+        let x = M::append(M::append(a.clone(), b.clone()), c.clone());
+        let y = M::append(a.clone(), M::append(b.clone(), c.clone()));
+        check_fun_equality(x, y)
+    }
+
+    #[quickcheck]
+    #[allow(clippy::clone_on_copy)]
+    fn test_option(a: Option<usize>, b: Option<usize>, c: Option<usize>) {
+        check_identity::<Option<Max>>(a.clone());
+        check_associative::<Option<Max>>(a, b, c);
+    }
+
+    #[quickcheck]
+    fn test_par_accumulate(xs: Vec<Vec<usize>>) {
+        assert_eq!(
+            VecAppend::<usize>::par_accumulate(xs.clone()),
+            VecAppend::<usize>::accumulate(xs)
+        );
+    }
+
+    #[quickcheck]
+    fn test_par_accumulate_tuple(xs: Vec<(usize, String)>) {
+        assert_eq!(
+            <(SaturatingAdd, StringAppend)>::par_accumulate(xs.clone()),
+            <(SaturatingAdd, StringAppend)>::accumulate(xs)
+        );
+    }
+
+    #[quickcheck]
+    fn test_accumulate_balanced(xs: Vec<String>) {
+        assert_eq!(
+            StringAppend::accumulate_balanced(&xs),
+            StringAppend::accumulate(xs)
+        );
+    }
+
+    fn naive_times<A: Monoid>(a: A::T, n: usize) -> A::T
+    where
+        A::T: Clone,
+    {
+        let mut res = A::identity();
+        for _ in 0..n {
+            res = A::append(res, a.clone());
+        }
+        res
+    }
+
+    fn check_times<A: Monoid>(a: A::T, n: u8)
+    where
+        A::T: Clone + std::fmt::Debug + PartialEq,
+    {
+        let n = n as usize;
+        assert_eq!(A::times(a.clone(), n), naive_times::<A>(a.clone(), n));
+        if n > 0 {
+            assert_eq!(A::times1(a.clone(), n), naive_times::<A>(a, n));
+        }
+    }
+
+    #[quickcheck]
+    fn test_times(a: usize, v: Vec<usize>, s: String, o: Option<usize>, n: u8) {
+        check_times::<SaturatingAdd>(a, n);
+        check_times::<SaturatingMul>(a, n);
+        check_times::<WrappingMul>(a, n);
+        check_times::<Max>(a, n);
+        check_times::<VecAppend<usize>>(v.clone(), n);
+        check_times::<StringAppend>(s.clone(), n);
+        check_times::<()>((), n);
+        check_times::<(SaturatingAdd,)>((a,), n);
+        check_times::<(SaturatingAdd, SaturatingMul)>((a, a), n);
+        check_times::<(SaturatingAdd, SaturatingMul, VecAppend<usize>)>((a, a, v), n);
+        check_times::<Option<Max>>(o, n);
+        check_times::<Option<StringAppend>>(Some(s), n);
+    }
+
+    #[test]
+    fn test_times_fn() {
+        let f: Fun<usize, usize> = Rc::new(|x: usize| x.saturating_mul(3).saturating_add(1));
+        for n in 0..20 {
+            check_fun_equality(
+                TraverseFn::<usize, SaturatingAdd>::times(f.clone(), n),
+                naive_times::<TraverseFn<usize, SaturatingAdd>>(f.clone(), n),
+            );
+            check_fun_equality(
+                ComposeEndomorphism::<usize>::times(f.clone(), n),
+                naive_times::<ComposeEndomorphism<usize>>(f.clone(), n),
+            );
+        }
+    }
+
+    #[test]
+    fn test_times_is_fast() {
+        assert_eq!(Add::<usize>::times(1, 1_000_000_000_000), 1_000_000_000_000);
+        assert_eq!(Mul::<usize>::times(1, usize::MAX), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_times1_zero() {
+        Add::times1(1, 0);
+    }
+
+    #[quickcheck]
+    fn test_commutative(a: usize, b: usize, x: Option<usize>, y: Option<usize>) {
+        check_commutative::<SaturatingAdd>(a, b);
+        check_commutative::<SaturatingMul>(a, b);
+        check_commutative::<Max>(a, b);
+        check_commutative::<(SaturatingAdd, SaturatingMul, Max)>((a, a, a), (b, b, b));
+        check_commutative::<Option<Max>>(x, y);
+    }
+
+    #[quickcheck]
+    fn test_wrapping_add(a: isize, b: isize, c: isize) {
+        check_identity::<WrappingAdd<isize>>(a);
+        check_associative::<WrappingAdd<isize>>(a, b, c);
+        check_commutative::<WrappingAdd<isize>>(a, b);
+        check_inverse::<WrappingAdd<isize>>(a);
+    }
+
+    #[quickcheck]
+    fn test_checked_add(a: Option<usize>, b: Option<usize>, c: Option<usize>) {
+        check_identity::<CheckedAdd>(a);
+        check_associative::<CheckedAdd>(a, b, c);
+        check_commutative::<CheckedAdd>(a, b);
+    }
+
+    #[test]
+    fn test_checked_add_overflow() {
+        assert_eq!(
+            CheckedAdd::accumulate([Some(usize::MAX), Some(1), Some(0)]),
+            None
+        );
+    }
+
+    #[quickcheck]
+    fn test_xor(a: usize, b: usize, c: usize) {
+        check_identity::<Xor>(a);
+        check_associative::<Xor>(a, b, c);
+        check_commutative::<Xor>(a, b);
+        check_inverse::<Xor>(a);
+        check_inverse::<(Xor, WrappingAdd<isize>)>((a, b as isize));
+    }
+
+    #[test]
+    fn test_compose_bijection() {
+        type M = ComposeBijection<usize>;
+        fn bijection(
+            forward: fn(usize) -> usize,
+            backward: fn(usize) -> usize,
+        ) -> Bijection<usize> {
+            Bijection {
+                forward: Rc::new(forward),
+                backward: Rc::new(backward),
+            }
+        }
+        let a = bijection(|x| x.wrapping_add(3), |x| x.wrapping_sub(3));
+        let b = bijection(|x| x.rotate_left(5), |x| x.rotate_right(5));
+        let c = bijection(|x| x ^ 0x55, |x| x ^ 0x55);
+
+        // checking by hand because we can't really check functions for equality
+        let x = M::append(M::append(a.clone(), b.clone()), c.clone());
+        let y = M::append(a.clone(), M::append(b.clone(), c.clone()));
+        check_fun_equality(x.forward, y.forward);
+        check_fun_equality(x.backward, y.backward);
+
+        let abc = M::append(M::append(a.clone(), b.clone()), c.clone());
+        for f in [a, b, c, abc] {
+            let x = M::append(f.clone(), M::inverse(f.clone()));
+            check_fun_equality(x.forward, M::identity().forward);
+            let x = M::append(M::inverse(f.clone()), f);
+            check_fun_equality(x.forward, M::identity().forward);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Persisting partial `accumulate` results so batches can be merged later.
+//!
+//! Checkpoints are written with bincode one after another, so appending to a file after every
+//! batch and calling `Checkpoint::merge` on it later gives the same result as accumulating
+//! everything in one go.
+
+use std::{
+    io::{BufRead, Read, Write},
+    marker::PhantomData,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::Monoid;
+
+pub struct Checkpoint<M>(PhantomData<M>);
+impl<M> Checkpoint<M>
+where
+    M: Monoid,
+    M::T: Serialize + DeserializeOwned,
+{
+    pub fn write<W: Write>(writer: W, value: &M::T) -> bincode::Result<()> {
+        bincode::serialize_into(writer, value)
+    }
+
+    pub fn read<R: Read>(reader: R) -> bincode::Result<M::T> {
+        bincode::deserialize_from(reader)
+    }
+
+    /// Reads checkpoints until the end of `reader` and appends them in order.
+    pub fn merge<R: BufRead>(mut reader: R) -> bincode::Result<M::T> {
+        let mut acc = M::identity();
+        while !reader.fill_buf()?.is_empty() {
+            acc = M::append(acc, Self::read(&mut reader)?);
+        }
+        Ok(acc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::super::{stats::*, value::*, SaturatingAdd, SaturatingMul, VecAppend};
+    use super::*;
+
+    fn checkpoint_batches<M>(batches: &[Vec<M::T>]) -> Vec<u8>
+    where
+        M: Monoid,
+        M::T: Clone + Serialize + DeserializeOwned,
+    {
+        let mut buf = Vec::new();
+        for batch in batches {
+            Checkpoint::<M>::write(&mut buf, &M::accumulate(batch.iter().cloned())).unwrap();
+        }
+        buf
+    }
+
+    #[quickcheck]
+    fn test_merge_after_reload(batches: Vec<Vec<(usize, usize, Vec<usize>)>>) {
+        type M = (SaturatingAdd, SaturatingMul, VecAppend<usize>);
+        let buf = checkpoint_batches::<M>(&batches);
+        assert_eq!(
+            Checkpoint::<M>::merge(buf.as_slice()).unwrap(),
+            M::accumulate(batches.into_iter().flatten())
+        );
+    }
+
+    #[quickcheck]
+    fn test_merge_stats_after_reload(batches: Vec<Vec<u16>>) {
+        type M = (Count, HyperLogLog<8>, TopK<u16, 8>);
+        let summaries = batches
+            .iter()
+            .map(|batch| {
+                batch
+                    .iter()
+                    .map(|x| (1, HyperLogLogSketch::of(x), SpaceSaving::of(*x)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let buf = checkpoint_batches::<M>(&summaries);
+        let (count, distinct, top) = Checkpoint::<M>::merge(buf.as_slice()).unwrap();
+        let (single_count, single_distinct, _) = M::accumulate(summaries.into_iter().flatten());
+        assert_eq!(count, single_count);
+        assert_eq!(distinct, single_distinct);
+        // TopK only keeps its guarantees across merges
+        let all = batches.iter().flatten().collect::<Vec<_>>();
+        for (item, counter) in top.top() {
+            assert!(counter.count >= all.iter().filter(|&&x| x == item).count() as u64);
+        }
+    }
+
+    #[quickcheck]
+    fn test_merge_variance_after_reload(batches: Vec<Vec<i32>>) {
+        let mut buf = Vec::new();
+        for batch in &batches {
+            let summary =
+                Variance::accumulate(batch.iter().map(|&x| VarianceSummary::of(x.into())));
+            Checkpoint::<Variance>::write(&mut buf, &summary).unwrap();
+        }
+        let merged = Checkpoint::<Variance>::merge(buf.as_slice()).unwrap();
+        let single_pass = Variance::accumulate(
+            batches
+                .iter()
+                .flatten()
+                .map(|&x| VarianceSummary::of(x.into())),
+        );
+        // floats round differently depending on how they're merged
+        let approx_eq = |a: f64, b: f64| (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0);
+        assert_eq!(merged.count, single_pass.count);
+        assert!(approx_eq(merged.mean, single_pass.mean));
+        assert!(approx_eq(merged.m2, single_pass.m2));
+    }
+
+    #[test]
+    fn test_value_types() {
+        let mut buf = Vec::new();
+        Checkpoint::<Sum<u32>>::write(&mut buf, &Sum(3)).unwrap();
+        Checkpoint::<Sum<u32>>::write(&mut buf, &Sum(4)).unwrap();
+        assert_eq!(
+            Checkpoint::<Sum<u32>>::merge(buf.as_slice()).unwrap(),
+            Sum(7)
+        );
+
+        let value = Value::<VecAppend<u8>>(vec![1, 2, 3]);
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bytes, bincode::serialize(&vec![1u8, 2, 3]).unwrap());
+        assert_eq!(
+            bincode::deserialize::<Value<VecAppend<u8>>>(&bytes).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_invalid_sketch() {
+        let bytes = bincode::serialize(&HyperLogLogSketch::<4>::new()).unwrap();
+        assert!(bincode::deserialize::<HyperLogLogSketch<5>>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_too_many_counters() {
+        let mut summary = SpaceSaving::<u8, 4>::new();
+        for x in 0..4 {
+            summary.insert(x);
+        }
+        let bytes = bincode::serialize(&summary).unwrap();
+        assert_eq!(
+            bincode::deserialize::<SpaceSaving<u8, 4>>(&bytes).unwrap(),
+            summary
+        );
+        assert!(bincode::deserialize::<SpaceSaving<u8, 3>>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut buf = Vec::new();
+        Checkpoint::<VecAppend<u64>>::write(&mut buf, &vec![1, 2, 3]).unwrap();
+        buf.pop();
+        assert!(Checkpoint::<VecAppend<u64>>::merge(buf.as_slice()).is_err());
+    }
+}
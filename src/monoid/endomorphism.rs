@@ -0,0 +1,144 @@
+//! Composing endomorphisms without nesting closures.
+//!
+//! `ComposeEndomorphism` wraps every `append` in a new closure calling the two halves, so a
+//! million composed functions are a million nested calls and overflow the stack when applied.
+//! `EndomorphismChain` keeps the functions in a `Vec` instead and applies them in a loop, which
+//! needs no stack at all and makes composing a `Vec` append.
+
+use std::{ops::Deref, sync::Arc};
+
+use super::{Endomorphism, Monoid, Semigroup};
+
+/// `Send + Sync` endomorphism, e.g. for `par_accumulate`.
+pub type SyncEndomorphism<A> = Arc<dyn Fn(A) -> A + Send + Sync>;
+
+/// Composition of the functions behind the pointers `P` (`Endomorphism` or `SyncEndomorphism`).
+pub struct EndomorphismChain<P> {
+    /// In the order they are composed, so the last one is applied first.
+    fns: Vec<P>,
+}
+impl<P> EndomorphismChain<P> {
+    pub fn new(f: P) -> Self {
+        Self { fns: vec![f] }
+    }
+
+    /// Number of functions that are applied.
+    pub fn len(&self) -> usize {
+        self.fns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fns.is_empty()
+    }
+
+    pub fn apply<A>(&self, x: A) -> A
+    where
+        P: Deref,
+        P::Target: Fn(A) -> A,
+    {
+        self.fns.iter().rev().fold(x, |x, f| f(x))
+    }
+
+    fn compose(mut self, other: Self) -> Self {
+        self.fns.extend(other.fns);
+        self
+    }
+}
+impl<P: Clone> Clone for EndomorphismChain<P> {
+    fn clone(&self) -> Self {
+        Self {
+            fns: self.fns.clone(),
+        }
+    }
+}
+impl<A> From<Endomorphism<A>> for EndomorphismChain<Endomorphism<A>> {
+    fn from(f: Endomorphism<A>) -> Self {
+        Self::new(f)
+    }
+}
+impl<A> From<SyncEndomorphism<A>> for EndomorphismChain<SyncEndomorphism<A>> {
+    fn from(f: SyncEndomorphism<A>) -> Self {
+        Self::new(f)
+    }
+}
+
+/// Composition of `EndomorphismChain<P>`s for any pointer `P` (`Endomorphism<A>` or
+/// `SyncEndomorphism<A>`), in the same order as `ComposeEndomorphism`: `append(f, g)` applies `g`
+/// first.
+pub struct ComposeChain<P>(std::marker::PhantomData<P>);
+impl<P> Semigroup for ComposeChain<P> {
+    type T = EndomorphismChain<P>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        a.compose(b)
+    }
+}
+impl<P> Monoid for ComposeChain<P> {
+    fn identity() -> Self::T {
+        EndomorphismChain { fns: Vec::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::super::*;
+    use super::*;
+
+    fn step(op: u8) -> impl Fn(u64) -> u64 {
+        move |x| match op % 3 {
+            0 => x.wrapping_add(op as u64),
+            1 => x.wrapping_mul(op as u64),
+            _ => x.rotate_left(op as u32),
+        }
+    }
+
+    #[quickcheck]
+    fn test_same_as_closures(ops: Vec<u8>, x: u64) {
+        let nested = ComposeEndomorphism::accumulate(
+            ops.iter().map(|&op| Rc::new(step(op)) as Endomorphism<u64>),
+        );
+        let chain = ComposeChain::accumulate(
+            ops.iter()
+                .map(|&op| EndomorphismChain::new(Rc::new(step(op)) as Endomorphism<u64>)),
+        );
+        let sync = ComposeChain::par_accumulate(
+            ops.iter()
+                .map(|&op| EndomorphismChain::new(Arc::new(step(op)) as SyncEndomorphism<u64>)),
+        );
+        assert_eq!(chain.len(), ops.len());
+        assert_eq!(chain.apply(x), nested(x));
+        assert_eq!(sync.apply(x), nested(x));
+    }
+
+    #[quickcheck]
+    fn test_laws(a: Vec<u8>, b: Vec<u8>, c: Vec<u8>, x: u64) {
+        type M = ComposeChain<Endomorphism<u64>>;
+        let chain = |ops: &Vec<u8>| {
+            M::accumulate(
+                ops.iter()
+                    .map(|&op| EndomorphismChain::new(Rc::new(step(op)) as Endomorphism<u64>)),
+            )
+        };
+        let (a, b, c) = (chain(&a), chain(&b), chain(&c));
+        assert_eq!(M::append(a.clone(), M::identity()).apply(x), a.apply(x));
+        assert_eq!(M::append(M::identity(), a.clone()).apply(x), a.apply(x));
+        assert_eq!(
+            M::append(M::append(a.clone(), b.clone()), c.clone()).apply(x),
+            M::append(a, M::append(b, c)).apply(x)
+        );
+    }
+
+    #[test]
+    fn test_deep_composition() {
+        let n = 1_000_000;
+        let increment: SyncEndomorphism<usize> = Arc::new(|x| x + 1);
+        let chain = ComposeChain::times(increment.into(), n);
+        assert_eq!(chain.len(), n);
+        assert_eq!(
+            std::thread::spawn(move || chain.apply(0)).join().unwrap(),
+            n
+        );
+    }
+}
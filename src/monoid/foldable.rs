@@ -0,0 +1,185 @@
+//! Folding structures with any `Monoid` by mapping every element into the monoid on the fly.
+//!
+//! `fold_map::<M>(xs, f)` is the same as `M::accumulate(xs.map(f))`, just without requiring the
+//! items to already be `M::T`. It's also what `TraverseFn` is for functions: `TraverseFn<A, M>`
+//! accumulates a list of functions `A -> M::T` into one that folds their results.
+
+use std::collections::BTreeMap;
+
+use crate::type_list::{Cons, Nil};
+
+use super::Monoid;
+
+pub fn fold_map<M, I, F>(iter: I, mut f: F) -> M::T
+where
+    M: Monoid,
+    I: IntoIterator,
+    F: FnMut(I::Item) -> M::T,
+{
+    iter.into_iter()
+        .fold(M::identity(), |acc, x| M::append(acc, f(x)))
+}
+
+pub trait Foldable: Sized {
+    type Item;
+
+    fn fold_map<M, F>(self, f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(Self::Item) -> M::T;
+
+    fn fold<M>(self) -> M::T
+    where
+        M: Monoid<T = Self::Item>,
+    {
+        self.fold_map::<M, _>(|x| x)
+    }
+}
+
+impl<A> Foldable for Vec<A> {
+    type Item = A;
+
+    fn fold_map<M, F>(self, f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(A) -> M::T,
+    {
+        fold_map::<M, _, _>(self, f)
+    }
+}
+
+impl<A> Foldable for Option<A> {
+    type Item = A;
+
+    fn fold_map<M, F>(self, mut f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(A) -> M::T,
+    {
+        match self {
+            Some(a) => f(a),
+            None => M::identity(),
+        }
+    }
+}
+
+/// Folds the values in the order of their keys.
+impl<K, V> Foldable for BTreeMap<K, V> {
+    type Item = V;
+
+    fn fold_map<M, F>(self, f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(V) -> M::T,
+    {
+        fold_map::<M, _, _>(self.into_values(), f)
+    }
+}
+
+impl Foldable for Nil {
+    // `Nil` doesn't know what's supposed to be in the list so it works for any item
+    type Item = std::convert::Infallible;
+
+    fn fold_map<M, F>(self, _f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(Self::Item) -> M::T,
+    {
+        M::identity()
+    }
+}
+
+/// Recursion helper for `Cons` so the list can end with a `Nil` of any item type.
+pub trait FoldableList<A>: Sized {
+    fn fold_map_list<M, F>(self, f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(A) -> M::T;
+}
+impl<A> FoldableList<A> for Nil {
+    fn fold_map_list<M, F>(self, _f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(A) -> M::T,
+    {
+        M::identity()
+    }
+}
+impl<A, T: FoldableList<A>> FoldableList<A> for Cons<A, T> {
+    fn fold_map_list<M, F>(self, mut f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(A) -> M::T,
+    {
+        let head = f(self.head);
+        M::append(head, self.tail.fold_map_list::<M, _>(f))
+    }
+}
+
+impl<A, T: FoldableList<A>> Foldable for Cons<A, T> {
+    type Item = A;
+
+    fn fold_map<M, F>(self, f: F) -> M::T
+    where
+        M: Monoid,
+        F: FnMut(A) -> M::T,
+    {
+        self.fold_map_list::<M, _>(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::type_list::cons;
+    use quickcheck_macros::quickcheck;
+
+    use super::super::*;
+    use super::*;
+
+    #[quickcheck]
+    fn test_fold_map(xs: Vec<u8>) {
+        assert_eq!(
+            fold_map::<StringAppend, _, _>(xs.iter(), |x| x.to_string()),
+            StringAppend::accumulate(xs.iter().map(|x| x.to_string()))
+        );
+        assert_eq!(
+            xs.clone()
+                .fold_map::<(SaturatingAdd, Max), _>(|x| (x as usize, x as usize)),
+            (
+                xs.iter().map(|&x| x as usize).sum(),
+                xs.iter().map(|&x| x as usize).max().unwrap_or(0)
+            )
+        );
+    }
+
+    #[quickcheck]
+    fn test_fold(xs: Vec<String>) {
+        assert_eq!(xs.clone().fold::<StringAppend>(), xs.concat());
+    }
+
+    #[quickcheck]
+    fn test_option(x: Option<usize>) {
+        assert_eq!(
+            x.fold_map::<VecAppend<usize>, _>(|x| vec![x]),
+            Vec::from_iter(x)
+        );
+        assert_eq!(x.fold::<SaturatingAdd>(), x.unwrap_or(0));
+    }
+
+    #[quickcheck]
+    fn test_btree_map(m: BTreeMap<u8, String>) {
+        let expected = m.values().cloned().collect::<String>();
+        assert_eq!(m.fold::<StringAppend>(), expected);
+    }
+
+    #[test]
+    fn test_type_list() {
+        let list = cons(1usize, cons(2, cons(3, Nil)));
+        assert_eq!(list.fold_map::<SaturatingMul, _>(|x| x + 1), 24);
+
+        let list = cons("a", cons("b", cons("c", Nil)));
+        assert_eq!(list.fold_map::<StringAppend, _>(String::from), "abc");
+
+        assert_eq!(Nil.fold_map::<SaturatingAdd, _>(|_| 1), 0);
+    }
+}
@@ -0,0 +1,219 @@
+//! Property checks for the algebraic laws behind `Semigroup`, `Monoid` and friends.
+//!
+//! The `check_*` functions assert a single law for given values, the `assert_*_laws` functions
+//! let quickcheck generate the values, so a new instance can be verified with one line:
+//!
+//! ```
+//! use exploring_rust::monoid::{laws, SaturatingAdd, SaturatingMul, VecAppend};
+//!
+//! laws::assert_monoid_laws::<(SaturatingAdd, SaturatingMul, VecAppend<usize>)>();
+//! ```
+//!
+//! Functions can't be compared directly so function-valued instances are checked with
+//! `assert_fun_monoid_laws` which compares the results for arbitrary arguments.
+
+use std::fmt::Debug;
+
+use num_traits::{Float, NumCast};
+use quickcheck::{quickcheck, Arbitrary, Gen, TestResult, Testable};
+
+use super::{CommutativeMonoid, Fun, Group, Monoid, Semigroup};
+
+pub fn check_identity<A: Monoid>(a: A::T)
+where
+    A::T: Clone + Debug + PartialEq,
+{
+    assert_eq!(A::append(a.clone(), A::identity()), a);
+    assert_eq!(A::append(A::identity(), a.clone()), a);
+}
+
+pub fn check_associative<A: Semigroup>(a: A::T, b: A::T, c: A::T)
+where
+    A::T: Clone + Debug + PartialEq,
+{
+    assert_eq!(
+        A::append(A::append(a.clone(), b.clone()), c.clone()),
+        A::append(a, A::append(b, c))
+    );
+}
+
+/// Associativity up to rounding errors, floating point arithmetic is only approximately lawful.
+pub fn check_associative_approx<A: Semigroup>(a: A::T, b: A::T, c: A::T)
+where
+    A::T: Float + Debug,
+{
+    let x = A::append(A::append(a, b), c);
+    let y = A::append(a, A::append(b, c));
+    // a few ulps of slack relative to the biggest number involved (adding numbers of opposite
+    // signs can lose most of the precision of the result)
+    let tolerance = A::T::epsilon() * <A::T as NumCast>::from(16).unwrap();
+    let scale = x.abs().max(y.abs()).max(a.abs() + b.abs() + c.abs());
+    assert!(
+        x == y || (x - y).abs() <= tolerance * scale,
+        "{x:?} != {y:?}"
+    );
+}
+
+pub fn check_commutative<A: CommutativeMonoid>(a: A::T, b: A::T)
+where
+    A::T: Clone + Debug + PartialEq,
+{
+    assert_eq!(A::append(a.clone(), b.clone()), A::append(b, a));
+}
+
+pub fn check_inverse<A: Group>(a: A::T)
+where
+    A::T: Clone + Debug + PartialEq,
+{
+    assert_eq!(A::append(a.clone(), A::inverse(a.clone())), A::identity());
+    assert_eq!(A::append(A::inverse(a.clone()), a), A::identity());
+}
+
+pub fn assert_semigroup_laws<A: Semigroup>()
+where
+    A::T: Arbitrary + Debug + PartialEq,
+{
+    quickcheck(check_associative::<A> as fn(A::T, A::T, A::T));
+}
+
+pub fn assert_monoid_laws<A: Monoid>()
+where
+    A::T: Arbitrary + Debug + PartialEq,
+{
+    assert_semigroup_laws::<A>();
+    quickcheck(check_identity::<A> as fn(A::T));
+}
+
+pub fn assert_commutative_monoid_laws<A: CommutativeMonoid>()
+where
+    A::T: Arbitrary + Debug + PartialEq,
+{
+    assert_monoid_laws::<A>();
+    quickcheck(check_commutative::<A> as fn(A::T, A::T));
+}
+
+pub fn assert_group_laws<A: Group>()
+where
+    A::T: Arbitrary + Debug + PartialEq,
+{
+    assert_monoid_laws::<A>();
+    quickcheck(check_inverse::<A> as fn(A::T));
+}
+
+/// Checks that two functions return the same results for arbitrary arguments.
+pub struct EqualFunTestable<Args, Return> {
+    pub f: Fun<Args, Return>,
+    pub g: Fun<Args, Return>,
+}
+impl<Args, Return> EqualFunTestable<Args, Return>
+where
+    Args: Arbitrary + Clone,
+    Return: PartialEq,
+{
+    fn check(&self, x: Args) -> bool {
+        (self.f)(x.clone()) == (self.g)(x)
+    }
+
+    fn shrink_failure(&self, g: &mut Gen, args: Args) -> Option<TestResult> {
+        for t in args.shrink() {
+            let new_args = t.clone();
+            let r = self.check(new_args).result(g);
+            if r.is_failure() {
+                // The shrunk value *does* witness a failure, so keep
+                // trying to shrink it.
+                let shrunk = self.shrink_failure(g, t);
+
+                // If we couldn't witness a failure on any shrunk value,
+                // then return the failure we already have.
+                return Some(shrunk.unwrap_or(r));
+            }
+        }
+        None
+    }
+}
+impl<Args, Return> Testable for EqualFunTestable<Args, Return>
+where
+    Args: Arbitrary + Debug + 'static,
+    Return: PartialEq + Debug + 'static,
+{
+    fn result(&self, g: &mut Gen) -> TestResult {
+        let args: Args = Arbitrary::arbitrary(g);
+        let r = self.check(args.clone()).result(g);
+        if r.is_failure() {
+            return self.shrink_failure(g, args).unwrap_or(r);
+        }
+        r
+    }
+}
+
+pub fn check_fun_equality<Args, Return>(f: Fun<Args, Return>, g: Fun<Args, Return>)
+where
+    Args: Arbitrary + Debug + 'static,
+    Return: PartialEq + Debug + 'static,
+{
+    quickcheck(EqualFunTestable { f, g });
+}
+
+/// Checks identity for each of the given functions and associativity for them in order.
+pub fn assert_fun_monoid_laws<A, Args, Return>(
+    a: Fun<Args, Return>,
+    b: Fun<Args, Return>,
+    c: Fun<Args, Return>,
+) where
+    A: Monoid<T = Fun<Args, Return>>,
+    Args: Arbitrary + Debug + 'static,
+    Return: PartialEq + Debug + 'static,
+{
+    for f in [a.clone(), b.clone(), c.clone()] {
+        check_fun_equality(A::append(f.clone(), A::identity()), f.clone());
+        check_fun_equality(A::append(A::identity(), f.clone()), f);
+    }
+    check_fun_equality(
+        A::append(A::append(a.clone(), b.clone()), c.clone()),
+        A::append(a, A::append(b, c)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn test_laws() {
+        assert_commutative_monoid_laws::<(SaturatingAdd, SaturatingMul, Max)>();
+        assert_monoid_laws::<(VecAppend<usize>, StringAppend)>();
+        assert_monoid_laws::<Option<StringAppend>>();
+        assert_group_laws::<(WrappingAdd, Xor)>();
+    }
+
+    #[test]
+    fn test_fun_laws() {
+        assert_fun_monoid_laws::<TraverseFn<usize, StringAppend>, _, _>(
+            Rc::new(|x: usize| x.to_string()),
+            Rc::new(|x: usize| "-".repeat(x % 5)),
+            Rc::new(|x: usize| format!("{x:x}")),
+        );
+        assert_fun_monoid_laws::<ComposeEndomorphism<String>, _, _>(
+            Rc::new(|s: String| s.to_uppercase()),
+            Rc::new(|s: String| s.chars().rev().collect()),
+            Rc::new(|s: String| s + "!"),
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_laws_catch_non_associative() {
+        struct Sub;
+        impl Semigroup for Sub {
+            type T = i32;
+
+            fn append(a: i32, b: i32) -> i32 {
+                a.wrapping_sub(b)
+            }
+        }
+        assert_semigroup_laws::<Sub>();
+    }
+}
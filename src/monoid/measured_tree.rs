@@ -0,0 +1,507 @@
+//! Balanced tree where every node caches the `Monoid` summary of its subtree.
+//!
+//! Because the summaries only need `append`, the same tree does very different jobs depending
+//! on the measure: counting with `SaturatingAdd` makes it a sequence with `O(log n)` indexing,
+//! adding a newline count turns it into a rope with line indexing and `(SaturatingAdd, Max)`
+//! answers range maximum queries. Splitting and concatenation are `O(log n)` (join based AVL
+//! tree), and so are the read-only `iter_from` and `fold_range` which just descend along the
+//! cached summaries.
+
+use super::{Max, Monoid, SaturatingAdd};
+
+/// How an item contributes to the summary of type `M::T`.
+pub trait Measured<M: Monoid> {
+    fn measure(&self) -> M::T;
+}
+
+pub struct MeasuredTree<M: Monoid, A> {
+    root: Option<Box<Node<M, A>>>,
+}
+
+struct Node<M: Monoid, A> {
+    left: MeasuredTree<M, A>,
+    item: A,
+    right: MeasuredTree<M, A>,
+    height: usize,
+    measure: M::T,
+}
+
+impl<M, A> MeasuredTree<M, A>
+where
+    M: Monoid,
+    M::T: Clone,
+    A: Measured<M>,
+{
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Summary of all the items.
+    pub fn measure(&self) -> M::T {
+        self.root
+            .as_ref()
+            .map_or_else(M::identity, |node| node.measure.clone())
+    }
+
+    pub fn push_back(&mut self, item: A) {
+        let tree = std::mem::take(self);
+        *self = Self::join(tree, item, Self::new());
+    }
+
+    pub fn push_front(&mut self, item: A) {
+        let tree = std::mem::take(self);
+        *self = Self::join(Self::new(), item, tree);
+    }
+
+    pub fn concat(self, other: Self) -> Self {
+        match self.split_last() {
+            Some((tree, last)) => Self::join(tree, last, other),
+            None => other,
+        }
+    }
+
+    /// Splits the tree before the first item for which `pred` holds on the summary of all the
+    /// items up to and including it. `pred` has to be monotone (once true stays true).
+    pub fn split<P>(self, pred: P) -> (Self, Self)
+    where
+        P: Fn(&M::T) -> bool,
+    {
+        self.split_with(M::identity(), &pred)
+    }
+
+    pub fn iter(&self) -> Iter<'_, M, A> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(self);
+        iter
+    }
+
+    /// Iterates over the items `split(pred)` would put into the right tree, finding the first
+    /// one takes `O(log n)` without changing the tree.
+    pub fn iter_from<P>(&self, pred: P) -> Iter<'_, M, A>
+    where
+        P: Fn(&M::T) -> bool,
+    {
+        let mut iter = Iter { stack: Vec::new() };
+        let mut acc = M::identity();
+        let mut tree = self;
+        while let Some(node) = tree.root.as_deref() {
+            let with_left = M::append(acc.clone(), node.left.measure());
+            if pred(&with_left) {
+                // the item is in the left subtree, so this node comes after it
+                iter.stack.push(node);
+                tree = &node.left;
+                continue;
+            }
+            let with_item = M::append(with_left, node.item.measure());
+            if pred(&with_item) {
+                iter.stack.push(node);
+                break;
+            }
+            acc = with_item;
+            tree = &node.right;
+        }
+        iter
+    }
+
+    /// Summary of the items `split(start)` puts into the right tree and `split(end)` into the
+    /// left one, in `O(log n)` from the cached summaries. Both predicates have to be monotone.
+    pub fn fold_range<P, Q>(&self, start: P, end: Q) -> M::T
+    where
+        P: Fn(&M::T) -> bool,
+        Q: Fn(&M::T) -> bool,
+    {
+        self.fold_range_with(&M::identity(), &start, &end)
+    }
+
+    fn height(&self) -> usize {
+        self.root.as_ref().map_or(0, |node| node.height)
+    }
+
+    fn node(left: Self, item: A, right: Self) -> Self {
+        let height = 1 + left.height().max(right.height());
+        let measure = M::append(M::append(left.measure(), item.measure()), right.measure());
+        Self {
+            root: Some(Box::new(Node {
+                left,
+                item,
+                right,
+                height,
+                measure,
+            })),
+        }
+    }
+
+    fn into_parts(self) -> Option<(Self, A, Self)> {
+        self.root.map(|node| {
+            let Node {
+                left, item, right, ..
+            } = *node;
+            (left, item, right)
+        })
+    }
+
+    fn rotate_left(self) -> Self {
+        match self.into_parts() {
+            Some((a, x, right)) => match right.into_parts() {
+                Some((b, y, c)) => Self::node(Self::node(a, x, b), y, c),
+                None => Self::node(a, x, Self::new()),
+            },
+            None => Self::new(),
+        }
+    }
+
+    fn rotate_right(self) -> Self {
+        match self.into_parts() {
+            Some((left, y, c)) => match left.into_parts() {
+                Some((a, x, b)) => Self::node(a, x, Self::node(b, y, c)),
+                None => Self::node(Self::new(), y, c),
+            },
+            None => Self::new(),
+        }
+    }
+
+    /// Tree with all the items of `left`, then `item` and then all the items of `right`.
+    fn join(left: Self, item: A, right: Self) -> Self {
+        if left.height() > right.height() + 1 {
+            Self::join_right(left, item, right)
+        } else if right.height() > left.height() + 1 {
+            Self::join_left(left, item, right)
+        } else {
+            Self::node(left, item, right)
+        }
+    }
+
+    /// `join` when `left` is taller.
+    fn join_right(left: Self, item: A, right: Self) -> Self {
+        let Some((ll, x, lr)) = left.into_parts() else {
+            return Self::node(Self::new(), item, right);
+        };
+        if lr.height() <= right.height() + 1 {
+            let tree = Self::node(lr, item, right);
+            if tree.height() <= ll.height() + 1 {
+                Self::node(ll, x, tree)
+            } else {
+                Self::node(ll, x, tree.rotate_right()).rotate_left()
+            }
+        } else {
+            let tree = Self::join_right(lr, item, right);
+            if tree.height() <= ll.height() + 1 {
+                Self::node(ll, x, tree)
+            } else {
+                Self::node(ll, x, tree).rotate_left()
+            }
+        }
+    }
+
+    /// `join` when `right` is taller.
+    fn join_left(left: Self, item: A, right: Self) -> Self {
+        let Some((rl, x, rr)) = right.into_parts() else {
+            return Self::node(left, item, Self::new());
+        };
+        if rl.height() <= left.height() + 1 {
+            let tree = Self::node(left, item, rl);
+            if tree.height() <= rr.height() + 1 {
+                Self::node(tree, x, rr)
+            } else {
+                Self::node(tree.rotate_left(), x, rr).rotate_right()
+            }
+        } else {
+            let tree = Self::join_left(left, item, rl);
+            if tree.height() <= rr.height() + 1 {
+                Self::node(tree, x, rr)
+            } else {
+                Self::node(tree, x, rr).rotate_right()
+            }
+        }
+    }
+
+    fn split_last(self) -> Option<(Self, A)> {
+        let (left, item, right) = self.into_parts()?;
+        Some(match right.split_last() {
+            Some((right, last)) => (Self::join(left, item, right), last),
+            None => (left, item),
+        })
+    }
+
+    /// `fold_range` for a subtree whose items come after the items summarised by `acc`.
+    fn fold_range_with<P, Q>(&self, acc: &M::T, start: &P, end: &Q) -> M::T
+    where
+        P: Fn(&M::T) -> bool,
+        Q: Fn(&M::T) -> bool,
+    {
+        let Some(node) = self.root.as_deref() else {
+            return M::identity();
+        };
+        let total = M::append(acc.clone(), node.measure.clone());
+        // by monotonicity the items before decide about the whole subtree
+        if !start(&total) || end(acc) {
+            return M::identity();
+        }
+        if start(acc) && !end(&total) {
+            return node.measure.clone();
+        }
+        let left = node.left.fold_range_with(acc, start, end);
+        let with_item = M::append(
+            M::append(acc.clone(), node.left.measure()),
+            node.item.measure(),
+        );
+        let item = if start(&with_item) && !end(&with_item) {
+            node.item.measure()
+        } else {
+            M::identity()
+        };
+        let right = node.right.fold_range_with(&with_item, start, end);
+        M::append(M::append(left, item), right)
+    }
+
+    fn split_with<P>(self, acc: M::T, pred: &P) -> (Self, Self)
+    where
+        P: Fn(&M::T) -> bool,
+    {
+        let Some((left, item, right)) = self.into_parts() else {
+            return (Self::new(), Self::new());
+        };
+        let with_left = M::append(acc.clone(), left.measure());
+        if pred(&with_left) {
+            let (ll, lr) = left.split_with(acc, pred);
+            return (ll, Self::join(lr, item, right));
+        }
+        let with_item = M::append(with_left, item.measure());
+        if pred(&with_item) {
+            return (left, Self::join(Self::new(), item, right));
+        }
+        let (rl, rr) = right.split_with(with_item, pred);
+        (Self::join(left, item, rl), rr)
+    }
+}
+
+impl<M, A> Default for MeasuredTree<M, A>
+where
+    M: Monoid,
+{
+    fn default() -> Self {
+        Self { root: None }
+    }
+}
+
+impl<M, A> FromIterator<A> for MeasuredTree<M, A>
+where
+    M: Monoid,
+    M::T: Clone,
+    A: Measured<M>,
+{
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for item in iter {
+            tree.push_back(item);
+        }
+        tree
+    }
+}
+
+pub struct Iter<'a, M: Monoid, A> {
+    stack: Vec<&'a Node<M, A>>,
+}
+impl<'a, M: Monoid, A> Iter<'a, M, A> {
+    fn push_left(&mut self, mut tree: &'a MeasuredTree<M, A>) {
+        while let Some(node) = tree.root.as_deref() {
+            self.stack.push(node);
+            tree = &node.left;
+        }
+    }
+}
+impl<'a, M: Monoid, A> Iterator for Iter<'a, M, A> {
+    type Item = &'a A;
+
+    fn next(&mut self) -> Option<&'a A> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        Some(&node.item)
+    }
+}
+
+/// Measure for a rope: number of chars and number of newlines.
+pub type TextMeasure = (SaturatingAdd, SaturatingAdd);
+
+impl Measured<TextMeasure> for char {
+    fn measure(&self) -> (usize, usize) {
+        (1, usize::from(*self == '\n'))
+    }
+}
+
+/// Text with `O(log n)` edits and line lookups.
+#[derive(Default)]
+pub struct Rope {
+    chars: MeasuredTree<TextMeasure, char>,
+}
+impl Rope {
+    pub fn len_chars(&self) -> usize {
+        self.chars.measure().0
+    }
+
+    pub fn len_lines(&self) -> usize {
+        self.chars.measure().1 + 1
+    }
+
+    pub fn insert(&mut self, at: usize, text: &str) {
+        let (left, right) = std::mem::take(&mut self.chars).split(|&(chars, _)| chars > at);
+        self.chars = left.concat(text.chars().collect()).concat(right);
+    }
+
+    /// Contents of the `n`-th line (without the newline).
+    pub fn line(&self, n: usize) -> String {
+        let mut chars = self.chars.iter_from(|&(_, lines)| lines >= n);
+        if n > 0 {
+            // skip the newline ending the previous line
+            chars.next();
+        }
+        chars.take_while(|&&c| c != '\n').collect()
+    }
+}
+impl std::fmt::Display for Rope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.chars.iter().try_for_each(|c| write!(f, "{c}"))
+    }
+}
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+        }
+    }
+}
+
+/// Measure for range queries: number of items and the biggest one.
+pub type RangeMaxMeasure = (SaturatingAdd, Max);
+
+impl Measured<RangeMaxMeasure> for usize {
+    fn measure(&self) -> (usize, usize) {
+        (1, *self)
+    }
+}
+
+/// Largest item in the index range `from..to` (or `0` when empty).
+pub fn range_max(tree: &MeasuredTree<RangeMaxMeasure, usize>, from: usize, to: usize) -> usize {
+    let (_, max) = tree.fold_range(|&(count, _)| count > from, |&(count, _)| count > to);
+    max
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::super::Semigroup;
+    use super::*;
+
+    fn check_balanced<M: Monoid, A>(tree: &MeasuredTree<M, A>) -> usize {
+        match tree.root.as_deref() {
+            Some(node) => {
+                let left = check_balanced(&node.left);
+                let right = check_balanced(&node.right);
+                assert!(left.abs_diff(right) <= 1);
+                assert_eq!(node.height, 1 + left.max(right));
+                node.height
+            }
+            None => 0,
+        }
+    }
+
+    #[quickcheck]
+    fn test_split(xs: Vec<usize>, at: usize) {
+        let at = at % (xs.len() + 1);
+        let tree = xs
+            .iter()
+            .copied()
+            .collect::<MeasuredTree<RangeMaxMeasure, _>>();
+        assert_eq!(
+            tree.measure(),
+            RangeMaxMeasure::accumulate(xs.iter().map(|&x| (1, x)))
+        );
+
+        assert!(tree.iter_from(|&(count, _)| count > at).eq(&xs[at..]));
+
+        let (left, right) = tree.split(|&(count, _)| count > at);
+        check_balanced(&left);
+        check_balanced(&right);
+        assert_eq!(left.iter().copied().collect::<Vec<_>>(), xs[..at]);
+        assert_eq!(right.iter().copied().collect::<Vec<_>>(), xs[at..]);
+        assert_eq!(
+            left.measure(),
+            RangeMaxMeasure::accumulate(xs[..at].iter().map(|&x| (1, x)))
+        );
+    }
+
+    #[quickcheck]
+    fn test_concat(xs: Vec<usize>, ys: Vec<usize>) {
+        let left = xs
+            .iter()
+            .copied()
+            .collect::<MeasuredTree<RangeMaxMeasure, _>>();
+        let right = ys
+            .iter()
+            .copied()
+            .collect::<MeasuredTree<RangeMaxMeasure, _>>();
+        let tree = left.concat(right);
+        check_balanced(&tree);
+        let all = xs.iter().chain(&ys).copied().collect::<Vec<_>>();
+        assert_eq!(tree.iter().copied().collect::<Vec<_>>(), all);
+        assert_eq!(
+            tree.measure(),
+            RangeMaxMeasure::append(
+                RangeMaxMeasure::accumulate(xs.iter().map(|&x| (1, x))),
+                RangeMaxMeasure::accumulate(ys.iter().map(|&x| (1, x)))
+            )
+        );
+    }
+
+    #[quickcheck]
+    fn test_range_max(xs: Vec<usize>, from: usize, to: usize) {
+        let (from, to) = (from % (xs.len() + 1), to % (xs.len() + 1));
+        let (from, to) = (from.min(to), from.max(to));
+        let tree = xs.iter().copied().collect();
+        let max = range_max(&tree, from, to);
+        assert_eq!(max, xs[from..to].iter().copied().max().unwrap_or(0));
+        assert_eq!(
+            tree.fold_range(|&(count, _)| count > from, |&(count, _)| count > to),
+            RangeMaxMeasure::accumulate(xs[from..to].iter().map(|&x| (1, x)))
+        );
+    }
+
+    #[test]
+    fn test_push_front() {
+        let mut tree = MeasuredTree::<RangeMaxMeasure, usize>::new();
+        for x in 0..1000 {
+            tree.push_front(x);
+        }
+        assert!(check_balanced(&tree) <= 15);
+        assert_eq!(tree.measure(), (1000, 999));
+        assert!(tree.iter().copied().eq((0..1000).rev()));
+    }
+
+    #[test]
+    fn test_rope() {
+        let mut rope = Rope::from("first\nthird");
+        rope.insert(6, "second\n");
+        assert_eq!(rope.to_string(), "first\nsecond\nthird");
+        assert_eq!(rope.len_chars(), 18);
+        assert_eq!(rope.len_lines(), 3);
+        assert_eq!(rope.line(0), "first");
+        assert_eq!(rope.line(1), "second");
+        assert_eq!(rope.line(2), "third");
+        assert_eq!(rope.line(3), "");
+    }
+
+    #[quickcheck]
+    fn test_rope_lines(lines: Vec<String>) {
+        let text = lines.join("\n");
+        let rope = Rope::from(text.as_str());
+        assert_eq!(rope.len_chars(), text.chars().count());
+        for (n, line) in text.split('\n').enumerate() {
+            assert_eq!(rope.line(n), line);
+        }
+    }
+}
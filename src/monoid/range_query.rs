@@ -0,0 +1,316 @@
+//! Array wrappers answering `M::accumulate` over any index range without visiting every item.
+
+use std::ops::{Bound, Range, RangeBounds};
+
+use super::{CommutativeMonoid, Group, Monoid};
+
+fn to_range(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => Some(start),
+        Bound::Excluded(&start) => start.checked_add(1),
+        Bound::Unbounded => Some(0),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1),
+        Bound::Excluded(&end) => Some(end),
+        Bound::Unbounded => Some(len),
+    };
+    match (start, end) {
+        (Some(start), Some(end)) if start <= end && end <= len => start..end,
+        _ => panic!("range out of bounds"),
+    }
+}
+
+/// Segment tree with `O(log n)` point updates and range queries for any `Monoid`.
+pub struct SegmentTree<M: Monoid> {
+    len: usize,
+    /// Implicit binary tree, leaves start at `nodes.len() / 2` (padded with identities to a power
+    /// of two so every node covers a contiguous range and non-commutative monoids work).
+    nodes: Vec<M::T>,
+}
+impl<M> SegmentTree<M>
+where
+    M: Monoid,
+    M::T: Clone,
+{
+    pub fn new(len: usize) -> Self {
+        Self::from_iter(std::iter::repeat_with(M::identity).take(len))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> &M::T {
+        assert!(index < self.len, "index out of bounds");
+        &self.nodes[self.nodes.len() / 2 + index]
+    }
+
+    pub fn set(&mut self, index: usize, value: M::T) {
+        assert!(index < self.len, "index out of bounds");
+        let mut node = self.nodes.len() / 2 + index;
+        self.nodes[node] = value;
+        while node > 1 {
+            node /= 2;
+            self.nodes[node] = M::append(
+                self.nodes[2 * node].clone(),
+                self.nodes[2 * node + 1].clone(),
+            );
+        }
+    }
+
+    pub fn query(&self, range: impl RangeBounds<usize>) -> M::T {
+        let Range { start, end } = to_range(range, self.len);
+        let offset = self.nodes.len() / 2;
+        let (mut left, mut right) = (start + offset, end + offset);
+        let (mut left_acc, mut right_acc) = (M::identity(), M::identity());
+        while left < right {
+            if left % 2 == 1 {
+                left_acc = M::append(left_acc, self.nodes[left].clone());
+                left += 1;
+            }
+            if right % 2 == 1 {
+                right -= 1;
+                right_acc = M::append(self.nodes[right].clone(), right_acc);
+            }
+            left /= 2;
+            right /= 2;
+        }
+        M::append(left_acc, right_acc)
+    }
+}
+impl<M> FromIterator<M::T> for SegmentTree<M>
+where
+    M: Monoid,
+    M::T: Clone,
+{
+    fn from_iter<I: IntoIterator<Item = M::T>>(iter: I) -> Self {
+        let items = iter.into_iter().collect::<Vec<_>>();
+        let len = items.len();
+        let offset = len.next_power_of_two();
+        let mut nodes = std::iter::repeat_with(M::identity)
+            .take(offset)
+            .chain(items)
+            .chain(std::iter::repeat_with(M::identity))
+            .take(2 * offset)
+            .collect::<Vec<_>>();
+        for node in (1..offset).rev() {
+            nodes[node] = M::append(nodes[2 * node].clone(), nodes[2 * node + 1].clone());
+        }
+        Self { len, nodes }
+    }
+}
+
+/// Fenwick (binary indexed) tree, a faster alternative to `SegmentTree` when the operation can
+/// be undone.
+///
+/// Range queries subtract prefixes and updates add a difference into the middle of partial
+/// sums, so besides inverses it also needs commutativity. The items are kept next to the partial
+/// sums so `get` can return references, that's `2n` values like in a `SegmentTree`.
+pub struct FenwickTree<G: Group> {
+    values: Vec<G::T>,
+    /// `partial[i - 1]` accumulates `values[i - (i & -i)..i]`.
+    partial: Vec<G::T>,
+}
+impl<G> FenwickTree<G>
+where
+    G: Group + CommutativeMonoid,
+    G::T: Clone,
+{
+    pub fn new(len: usize) -> Self {
+        Self {
+            values: std::iter::repeat_with(G::identity).take(len).collect(),
+            partial: std::iter::repeat_with(G::identity).take(len).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> &G::T {
+        assert!(index < self.len(), "index out of bounds");
+        &self.values[index]
+    }
+
+    /// Appends `delta` to the item at `index`.
+    pub fn add(&mut self, index: usize, delta: G::T) {
+        assert!(index < self.len(), "index out of bounds");
+        self.values[index] = G::append(self.values[index].clone(), delta.clone());
+        let mut i = index + 1;
+        while i <= self.partial.len() {
+            self.partial[i - 1] = G::append(self.partial[i - 1].clone(), delta.clone());
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    pub fn set(&mut self, index: usize, value: G::T) {
+        assert!(index < self.len(), "index out of bounds");
+        let delta = G::append(G::inverse(self.values[index].clone()), value);
+        self.add(index, delta);
+    }
+
+    /// Accumulation of the first `len` items.
+    pub fn prefix(&self, len: usize) -> G::T {
+        assert!(len <= self.len(), "range out of bounds");
+        let mut acc = G::identity();
+        let mut i = len;
+        while i > 0 {
+            acc = G::append(self.partial[i - 1].clone(), acc);
+            i -= i & i.wrapping_neg();
+        }
+        acc
+    }
+
+    pub fn query(&self, range: impl RangeBounds<usize>) -> G::T {
+        let Range { start, end } = to_range(range, self.len());
+        G::append(G::inverse(self.prefix(start)), self.prefix(end))
+    }
+}
+impl<G> FromIterator<G::T> for FenwickTree<G>
+where
+    G: Group + CommutativeMonoid,
+    G::T: Clone,
+{
+    fn from_iter<I: IntoIterator<Item = G::T>>(iter: I) -> Self {
+        let values = iter.into_iter().collect::<Vec<_>>();
+        let mut partial = values.clone();
+        // push every partial result to its parent in one pass
+        for i in 1..=partial.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= partial.len() {
+                partial[parent - 1] =
+                    G::append(partial[parent - 1].clone(), partial[i - 1].clone());
+            }
+        }
+        Self { values, partial }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::super::*;
+    use super::*;
+
+    #[quickcheck]
+    fn test_segment_tree(
+        mut xs: Vec<String>,
+        updates: Vec<(usize, String)>,
+        ranges: Vec<(usize, usize)>,
+    ) {
+        let mut tree = xs.iter().cloned().collect::<SegmentTree<StringAppend>>();
+        for (index, value) in updates {
+            if !xs.is_empty() {
+                let index = index % xs.len();
+                tree.set(index, value.clone());
+                xs[index] = value;
+            }
+        }
+        assert_eq!(tree.len(), xs.len());
+        assert_eq!(tree.query(..), xs.concat());
+        for (start, end) in ranges {
+            let (start, end) = (start % (xs.len() + 1), end % (xs.len() + 1));
+            let (start, end) = (start.min(end), start.max(end));
+            assert_eq!(
+                tree.query(start..end),
+                StringAppend::accumulate(xs[start..end].iter().cloned())
+            );
+        }
+    }
+
+    #[quickcheck]
+    fn test_segment_tree_tuple(xs: Vec<(usize, usize)>, start: usize, end: usize) {
+        let tree = xs
+            .iter()
+            .copied()
+            .collect::<SegmentTree<(SaturatingAdd, Max)>>();
+        let (start, end) = (start % (xs.len() + 1), end % (xs.len() + 1));
+        let (start, end) = (start.min(end), start.max(end));
+        assert_eq!(
+            tree.query(start..end),
+            <(SaturatingAdd, Max)>::accumulate(xs[start..end].iter().copied())
+        );
+    }
+
+    #[test]
+    fn test_segment_tree_new() {
+        let mut tree = SegmentTree::<SaturatingAdd>::new(5);
+        assert_eq!(tree.query(..), 0);
+        tree.set(3, 7);
+        tree.set(1, 2);
+        assert_eq!(tree.query(..=3), 9);
+        assert_eq!(tree.query(2..), 7);
+        assert_eq!(*tree.get(3), 7);
+    }
+
+    #[quickcheck]
+    fn test_fenwick_tree(
+        mut xs: Vec<(isize, usize)>,
+        updates: Vec<(usize, isize, usize)>,
+        ranges: Vec<(usize, usize)>,
+    ) {
+        type G = (WrappingAdd<isize>, Xor);
+        let mut tree = xs.iter().copied().collect::<FenwickTree<G>>();
+        for (index, a, b) in updates {
+            if !xs.is_empty() {
+                let index = index % xs.len();
+                if a % 2 == 0 {
+                    tree.set(index, (a, b));
+                    xs[index] = (a, b);
+                } else {
+                    tree.add(index, (a, b));
+                    xs[index] = G::append(xs[index], (a, b));
+                }
+            }
+        }
+        assert_eq!(tree.query(..), G::accumulate(xs.iter().copied()));
+        for (start, end) in ranges {
+            let (start, end) = (start % (xs.len() + 1), end % (xs.len() + 1));
+            let (start, end) = (start.min(end), start.max(end));
+            assert_eq!(
+                tree.query(start..end),
+                G::accumulate(xs[start..end].iter().copied())
+            );
+        }
+    }
+
+    #[test]
+    fn test_fenwick_tree_new() {
+        let mut tree = FenwickTree::<WrappingAdd<isize>>::new(10);
+        tree.add(2, 5);
+        tree.add(7, -3);
+        tree.set(2, 4);
+        assert_eq!(tree.prefix(3), 4);
+        assert_eq!(tree.query(3..), -3);
+        assert_eq!(tree.query(..), 1);
+        assert_eq!(*tree.get(7), -3);
+    }
+
+    #[test]
+    #[should_panic(expected = "range out of bounds")]
+    fn test_query_to_usize_max() {
+        SegmentTree::<SaturatingAdd>::new(3).query(..=usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "range out of bounds")]
+    fn test_query_after_usize_max() {
+        FenwickTree::<WrappingAdd>::new(3).query((Bound::Excluded(usize::MAX), Bound::Unbounded));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_fenwick_tree_set_out_of_bounds() {
+        FenwickTree::<WrappingAdd>::new(3).set(3, 1);
+    }
+}
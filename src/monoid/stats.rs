@@ -0,0 +1,510 @@
+//! Mergeable summaries of a stream of observations.
+//!
+//! Every summary is built from single observations (`of`) and combined with `append`, so a
+//! whole batch of metrics can be aggregated with one `accumulate` (or `par_accumulate`) call.
+//! The floating point ones (`Mean`, `Variance`) are only lawful up to rounding errors.
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use super::{CommutativeMonoid, Monoid, Semigroup};
+
+/// Number of observations.
+pub struct Count;
+impl Semigroup for Count {
+    type T = u64;
+
+    fn append(a: u64, b: u64) -> u64 {
+        a.saturating_add(b)
+    }
+}
+impl Monoid for Count {
+    fn identity() -> u64 {
+        0
+    }
+}
+impl CommutativeMonoid for Count {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MeanSummary {
+    pub count: u64,
+    pub mean: f64,
+}
+impl MeanSummary {
+    pub fn of(x: f64) -> Self {
+        Self { count: 1, mean: x }
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+}
+
+/// Running mean, merged with weights so it doesn't have to keep the (possibly huge) sum.
+pub struct Mean;
+impl Semigroup for Mean {
+    type T = MeanSummary;
+
+    fn append(a: MeanSummary, b: MeanSummary) -> MeanSummary {
+        if a.count == 0 {
+            return b;
+        }
+        if b.count == 0 {
+            return a;
+        }
+        let count = a.count + b.count;
+        MeanSummary {
+            count,
+            mean: a.mean + (b.mean - a.mean) * (b.count as f64 / count as f64),
+        }
+    }
+}
+impl Monoid for Mean {
+    fn identity() -> MeanSummary {
+        MeanSummary::default()
+    }
+}
+impl CommutativeMonoid for Mean {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct VarianceSummary {
+    pub count: u64,
+    pub mean: f64,
+    /// Sum of squared differences from the mean.
+    pub m2: f64,
+}
+impl VarianceSummary {
+    pub fn of(x: f64) -> Self {
+        Self {
+            count: 1,
+            mean: x,
+            m2: 0.0,
+        }
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// Population variance.
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+
+    /// Unbiased (Bessel corrected) sample variance.
+    pub fn sample_variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.m2 / (self.count - 1) as f64)
+    }
+}
+
+/// Welford's online variance with the Chan et al. formula for merging two partial results.
+pub struct Variance;
+impl Semigroup for Variance {
+    type T = VarianceSummary;
+
+    fn append(a: VarianceSummary, b: VarianceSummary) -> VarianceSummary {
+        if a.count == 0 {
+            return b;
+        }
+        if b.count == 0 {
+            return a;
+        }
+        let count = a.count + b.count;
+        let delta = b.mean - a.mean;
+        let weight = a.count as f64 * b.count as f64 / count as f64;
+        VarianceSummary {
+            count,
+            mean: a.mean + delta * (b.count as f64 / count as f64),
+            m2: a.m2 + b.m2 + delta * delta * weight,
+        }
+    }
+}
+impl Monoid for Variance {
+    fn identity() -> VarianceSummary {
+        VarianceSummary::default()
+    }
+}
+impl CommutativeMonoid for Variance {}
+
+/// HyperLogLog sketch with `2^P` registers, the relative error is about `1.04 / sqrt(2^P)`.
+///
+/// Items are hashed with the std `DefaultHasher` so sketches are only comparable when built
+/// by the same Rust release.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(transparent)]
+pub struct HyperLogLogSketch<const P: u32> {
+    registers: Box<[u8]>,
+}
+impl<const P: u32> HyperLogLogSketch<P> {
+    pub fn new() -> Self {
+        const { assert!(4 <= P && P <= 16, "precision has to be between 4 and 16") };
+        Self {
+            registers: vec![0; 1 << P].into_boxed_slice(),
+        }
+    }
+
+    pub fn of<H: Hash + ?Sized>(item: &H) -> Self {
+        let mut sketch = Self::new();
+        sketch.insert(item);
+        sketch
+    }
+
+    pub fn insert<H: Hash + ?Sized>(&mut self, item: &H) {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // first P bits pick the register, the rest is used for the rank
+        let index = (hash >> (64 - P)) as usize;
+        let rank = ((hash << P).leading_zeros().min(64 - P) + 1) as u8;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match P {
+            4 => 0.673,
+            5 => 0.697,
+            6 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&r| 2f64.powi(-i32::from(r)))
+            .sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            // linear counting is more precise for small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        }
+    }
+}
+impl<'de, const P: u32> serde::Deserialize<'de> for HyperLogLogSketch<P> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let registers = Box::<[u8]>::deserialize(deserializer)?;
+        if registers.len() != 1 << P {
+            return Err(serde::de::Error::invalid_length(
+                registers.len(),
+                &"2^P registers",
+            ));
+        }
+        Ok(Self { registers })
+    }
+}
+impl<const P: u32> Default for HyperLogLogSketch<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Approximate number of distinct items.
+pub struct HyperLogLog<const P: u32 = 12>;
+impl<const P: u32> Semigroup for HyperLogLog<P> {
+    type T = HyperLogLogSketch<P>;
+
+    fn append(mut a: Self::T, b: Self::T) -> Self::T {
+        for (a, b) in a.registers.iter_mut().zip(b.registers.iter()) {
+            *a = (*a).max(*b);
+        }
+        a
+    }
+}
+impl<const P: u32> Monoid for HyperLogLog<P> {
+    fn identity() -> Self::T {
+        HyperLogLogSketch::new()
+    }
+}
+impl<const P: u32> CommutativeMonoid for HyperLogLog<P> {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Counter {
+    /// Overestimate of the number of occurrences.
+    pub count: u64,
+    /// How much `count` can be over the real number.
+    pub error: u64,
+}
+
+/// Space-Saving summary keeping at most `K` counters.
+///
+/// Every item that occurs more than `n / K` times out of `n` is guaranteed to have a counter.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct SpaceSaving<T: Ord, const K: usize> {
+    counters: BTreeMap<T, Counter>,
+}
+impl<T: Ord + Clone, const K: usize> SpaceSaving<T, K> {
+    pub fn new() -> Self {
+        Self {
+            counters: BTreeMap::new(),
+        }
+    }
+
+    pub fn of(item: T) -> Self {
+        let mut summary = Self::new();
+        summary.insert(item);
+        summary
+    }
+
+    pub fn insert(&mut self, item: T) {
+        if let Some(counter) = self.counters.get_mut(&item) {
+            counter.count += 1;
+        } else if self.counters.len() < K {
+            self.counters.insert(item, Counter { count: 1, error: 0 });
+        } else if let Some((evicted, min)) = self.min_counter() {
+            self.counters.remove(&evicted);
+            self.counters.insert(
+                item,
+                Counter {
+                    count: min + 1,
+                    error: min,
+                },
+            );
+        }
+    }
+
+    /// Counters sorted from the most frequent item.
+    pub fn top(&self) -> Vec<(&T, Counter)> {
+        let mut top = self
+            .counters
+            .iter()
+            .map(|(item, counter)| (item, *counter))
+            .collect::<Vec<_>>();
+        top.sort_by(|(a, x), (b, y)| y.count.cmp(&x.count).then_with(|| a.cmp(b)));
+        top
+    }
+
+    fn min_counter(&self) -> Option<(T, u64)> {
+        self.counters
+            .iter()
+            .min_by(|(a, x), (b, y)| x.count.cmp(&y.count).then_with(|| b.cmp(a)))
+            .map(|(item, counter)| (item.clone(), counter.count))
+    }
+
+    /// Count that an item without a counter could have had.
+    fn missing_count(&self) -> u64 {
+        if self.counters.len() < K {
+            0
+        } else {
+            self.min_counter().map_or(0, |(_, min)| min)
+        }
+    }
+}
+impl<'de, T, const K: usize> serde::Deserialize<'de> for SpaceSaving<T, K>
+where
+    T: Ord + serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "SpaceSaving")]
+        struct Raw<T: Ord> {
+            counters: BTreeMap<T, Counter>,
+        }
+
+        let Raw { counters } = Raw::deserialize(deserializer)?;
+        if counters.len() > K {
+            return Err(serde::de::Error::invalid_length(
+                counters.len(),
+                &"at most K counters",
+            ));
+        }
+        Ok(Self { counters })
+    }
+}
+impl<T: Ord + Clone, const K: usize> Default for SpaceSaving<T, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Approximate `K` most frequent items (merged as in Agarwal et al. "Mergeable Summaries").
+///
+/// Merging is exact (and so lawful) as long as there are at most `K` distinct items,
+/// with more it only keeps the Space-Saving guarantees.
+pub struct TopK<T, const K: usize>(PhantomData<T>);
+impl<T: Ord + Clone, const K: usize> Semigroup for TopK<T, K> {
+    type T = SpaceSaving<T, K>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        let (a_missing, b_missing) = (a.missing_count(), b.missing_count());
+        let missing = |count| Counter {
+            count,
+            error: count,
+        };
+        let mut counters = BTreeMap::new();
+        for item in a.counters.keys().chain(b.counters.keys()) {
+            if counters.contains_key(item) {
+                continue;
+            }
+            let x = a.counters.get(item).copied().unwrap_or(missing(a_missing));
+            let y = b.counters.get(item).copied().unwrap_or(missing(b_missing));
+            let counter = Counter {
+                count: x.count + y.count,
+                error: x.error + y.error,
+            };
+            counters.insert(item.clone(), counter);
+        }
+        let mut merged = SpaceSaving { counters };
+        if merged.counters.len() > K {
+            let keep = merged
+                .top()
+                .into_iter()
+                .take(K)
+                .map(|(item, _)| item.clone())
+                .collect::<Vec<_>>();
+            merged.counters.retain(|item, _| keep.contains(item));
+        }
+        merged
+    }
+}
+impl<T: Ord + Clone, const K: usize> Monoid for TopK<T, K> {
+    fn identity() -> Self::T {
+        SpaceSaving::new()
+    }
+}
+impl<T: Ord + Clone, const K: usize> CommutativeMonoid for TopK<T, K> {}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::super::laws::*;
+    use super::*;
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+    }
+
+    #[quickcheck]
+    fn test_count(xs: Vec<u8>) {
+        assert_eq!(Count::accumulate(xs.iter().map(|_| 1)), xs.len() as u64);
+    }
+
+    #[quickcheck]
+    fn test_mean(xs: Vec<i32>, ys: Vec<i32>) {
+        let all = xs
+            .iter()
+            .chain(&ys)
+            .map(|&x| f64::from(x))
+            .collect::<Vec<_>>();
+        let merged = Mean::append(
+            Mean::accumulate(xs.iter().map(|&x| MeanSummary::of(f64::from(x)))),
+            Mean::accumulate(ys.iter().map(|&x| MeanSummary::of(f64::from(x)))),
+        );
+        assert_eq!(merged.count, all.len() as u64);
+        match merged.mean() {
+            Some(mean) => assert!(approx_eq(mean, all.iter().sum::<f64>() / all.len() as f64)),
+            None => assert!(all.is_empty()),
+        }
+    }
+
+    #[quickcheck]
+    fn test_variance(xs: Vec<i32>) {
+        let summary =
+            Variance::par_accumulate(xs.iter().map(|&x| VarianceSummary::of(f64::from(x))));
+        let sequential =
+            Variance::accumulate(xs.iter().map(|&x| VarianceSummary::of(f64::from(x))));
+        assert_eq!(summary.count, sequential.count);
+        if xs.is_empty() {
+            assert_eq!(summary.variance(), None);
+            return;
+        }
+        let n = xs.len() as f64;
+        let mean = xs.iter().map(|&x| f64::from(x)).sum::<f64>() / n;
+        let variance = xs
+            .iter()
+            .map(|&x| (f64::from(x) - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        assert!(approx_eq(summary.mean().unwrap(), mean));
+        assert!(approx_eq(summary.variance().unwrap(), variance));
+        assert!(approx_eq(sequential.variance().unwrap(), variance));
+    }
+
+    #[test]
+    fn test_sample_variance() {
+        let summary =
+            Variance::accumulate([2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].map(VarianceSummary::of));
+        assert_eq!(summary.variance(), Some(4.0));
+        assert_eq!(summary.sample_variance(), Some(32.0 / 7.0));
+        assert_eq!(VarianceSummary::of(1.0).sample_variance(), None);
+    }
+
+    fn sketch(items: &[u32]) -> HyperLogLogSketch<8> {
+        HyperLogLog::accumulate(items.iter().map(HyperLogLogSketch::of))
+    }
+
+    #[quickcheck]
+    fn test_hyperloglog_laws(a: Vec<u32>, b: Vec<u32>, c: Vec<u32>) {
+        check_identity::<HyperLogLog<8>>(sketch(&a));
+        check_associative::<HyperLogLog<8>>(sketch(&a), sketch(&b), sketch(&c));
+        check_commutative::<HyperLogLog<8>>(sketch(&a), sketch(&b));
+        // merging is the same as inserting everything into one sketch
+        let all = a.iter().chain(&b).copied().collect::<Vec<_>>();
+        assert_eq!(HyperLogLog::append(sketch(&a), sketch(&b)), sketch(&all));
+    }
+
+    #[test]
+    fn test_hyperloglog_estimate() {
+        for n in [10, 1_000, 100_000] {
+            // every item lands in two of the partial sketches
+            let sketch = HyperLogLog::<12>::par_accumulate((0..8).map(|part| {
+                let mut sketch = HyperLogLogSketch::new();
+                for i in (part..2 * n).step_by(8) {
+                    sketch.insert(&(i % n));
+                }
+                sketch
+            }));
+            let error = (sketch.estimate() - n as f64).abs() / n as f64;
+            assert!(error < 0.05, "estimate {} for {n}", sketch.estimate());
+        }
+    }
+
+    fn top_k<const K: usize>(items: &[u8]) -> SpaceSaving<u8, K> {
+        TopK::accumulate(items.iter().map(|&x| SpaceSaving::of(x)))
+    }
+
+    #[quickcheck]
+    fn test_top_k_exact(a: Vec<u8>, b: Vec<u8>, c: Vec<u8>) {
+        // 256 counters are enough to count every u8 exactly
+        check_identity::<TopK<u8, 256>>(top_k(&a));
+        check_associative::<TopK<u8, 256>>(top_k(&a), top_k(&b), top_k(&c));
+        check_commutative::<TopK<u8, 256>>(top_k(&a), top_k(&b));
+        for (item, counter) in top_k::<256>(&a).top() {
+            assert_eq!(counter.error, 0);
+            assert_eq!(
+                counter.count,
+                a.iter().filter(|&x| x == item).count() as u64
+            );
+        }
+    }
+
+    #[quickcheck]
+    fn test_top_k_guarantees(a: Vec<u8>, b: Vec<u8>) {
+        let merged = TopK::append(top_k::<4>(&a), top_k::<4>(&b));
+        check_identity::<TopK<u8, 4>>(merged.clone());
+        let all = a.iter().chain(&b).copied().collect::<Vec<_>>();
+        let true_count = |item: u8| all.iter().filter(|&&x| x == item).count() as u64;
+        for (&item, counter) in merged.top() {
+            assert!(counter.count >= true_count(item));
+            assert!(counter.count - counter.error <= true_count(item));
+        }
+        for item in all.iter().copied() {
+            if true_count(item) * 4 > all.len() as u64 {
+                assert!(merged.counters.contains_key(&item));
+            }
+        }
+    }
+
+    #[test]
+    fn test_top_k_heavy_hitters() {
+        let items = (0..1000u32).map(|i| if i % 3 == 0 { 7 } else { i });
+        let summary = TopK::<u32, 10>::par_accumulate(items.map(SpaceSaving::of));
+        assert_eq!(summary.top()[0].0, &7);
+    }
+}
@@ -0,0 +1,370 @@
+//! Traversing structures with effects, the big sibling of `Foldable`.
+//!
+//! `fold_map` combines the results of `f` with a monoid, `traverse` combines them with an
+//! `Applicative` which keeps the shape of the structure. `TraverseFn` only ever combines plain
+//! monoid values, so e.g. validating every item and either getting back all items or all errors
+//! needs this layer.
+//!
+//! Rust has no higher kinded types, so like the monoids an applicative is a tag type and its
+//! values are `Tag::F<A>`.
+
+use super::{Monoid, Semigroup};
+
+pub trait Applicative {
+    type F<A>;
+
+    fn pure<A>(a: A) -> Self::F<A>;
+
+    fn map<A, B, G>(fa: Self::F<A>, g: G) -> Self::F<B>
+    where
+        G: FnOnce(A) -> B;
+
+    /// Combines the effects of both arguments (in order) and the values with `g`.
+    fn map2<A, B, C, G>(fa: Self::F<A>, fb: Self::F<B>, g: G) -> Self::F<C>
+    where
+        G: FnOnce(A, B) -> C;
+}
+
+/// Short-circuits on the first `None`.
+pub struct OptionApplicative;
+impl Applicative for OptionApplicative {
+    type F<A> = Option<A>;
+
+    fn pure<A>(a: A) -> Option<A> {
+        Some(a)
+    }
+
+    fn map<A, B, G>(fa: Option<A>, g: G) -> Option<B>
+    where
+        G: FnOnce(A) -> B,
+    {
+        fa.map(g)
+    }
+
+    fn map2<A, B, C, G>(fa: Option<A>, fb: Option<B>, g: G) -> Option<C>
+    where
+        G: FnOnce(A, B) -> C,
+    {
+        Some(g(fa?, fb?))
+    }
+}
+
+/// Short-circuits on the first error.
+pub struct ResultApplicative<E>(std::marker::PhantomData<E>);
+impl<E> Applicative for ResultApplicative<E> {
+    type F<A> = Result<A, E>;
+
+    fn pure<A>(a: A) -> Result<A, E> {
+        Ok(a)
+    }
+
+    fn map<A, B, G>(fa: Result<A, E>, g: G) -> Result<B, E>
+    where
+        G: FnOnce(A) -> B,
+    {
+        fa.map(g)
+    }
+
+    fn map2<A, B, C, G>(fa: Result<A, E>, fb: Result<B, E>, g: G) -> Result<C, E>
+    where
+        G: FnOnce(A, B) -> C,
+    {
+        Ok(g(fa?, fb?))
+    }
+}
+
+/// Like `Result` but errors are accumulated with the semigroup `E` instead of keeping only the
+/// first one.
+pub enum Validation<E: Semigroup, T> {
+    Valid(T),
+    Invalid(E::T),
+}
+impl<E: Semigroup, T> Validation<E, T> {
+    pub fn into_result(self) -> Result<T, E::T> {
+        match self {
+            Validation::Valid(t) => Ok(t),
+            Validation::Invalid(e) => Err(e),
+        }
+    }
+}
+impl<E: Semigroup, T> From<Result<T, E::T>> for Validation<E, T> {
+    fn from(result: Result<T, E::T>) -> Self {
+        match result {
+            Ok(t) => Validation::Valid(t),
+            Err(e) => Validation::Invalid(e),
+        }
+    }
+}
+impl<E: Semigroup, T: Clone> Clone for Validation<E, T>
+where
+    E::T: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Validation::Valid(t) => Validation::Valid(t.clone()),
+            Validation::Invalid(e) => Validation::Invalid(e.clone()),
+        }
+    }
+}
+impl<E: Semigroup, T: std::fmt::Debug> std::fmt::Debug for Validation<E, T>
+where
+    E::T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Validation::Valid(t) => f.debug_tuple("Valid").field(t).finish(),
+            Validation::Invalid(e) => f.debug_tuple("Invalid").field(e).finish(),
+        }
+    }
+}
+impl<E: Semigroup, T: PartialEq> PartialEq for Validation<E, T>
+where
+    E::T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Validation::Valid(a), Validation::Valid(b)) => a == b,
+            (Validation::Invalid(a), Validation::Invalid(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Errors win over values, so accumulating validated monoid values either gives the accumulated
+/// values or all the errors (like `Option<A>`, the type doubles as its own tag).
+impl<E: Semigroup, M: Semigroup> Semigroup for Validation<E, M> {
+    type T = Validation<E, M::T>;
+
+    fn append(a: Self::T, b: Self::T) -> Self::T {
+        ValidationApplicative::<E>::map2(a, b, M::append)
+    }
+}
+impl<E: Semigroup, M: Monoid> Monoid for Validation<E, M> {
+    fn identity() -> Self::T {
+        Validation::Valid(M::identity())
+    }
+}
+
+pub struct ValidationApplicative<E>(std::marker::PhantomData<E>);
+impl<E: Semigroup> Applicative for ValidationApplicative<E> {
+    type F<A> = Validation<E, A>;
+
+    fn pure<A>(a: A) -> Validation<E, A> {
+        Validation::Valid(a)
+    }
+
+    fn map<A, B, G>(fa: Validation<E, A>, g: G) -> Validation<E, B>
+    where
+        G: FnOnce(A) -> B,
+    {
+        match fa {
+            Validation::Valid(a) => Validation::Valid(g(a)),
+            Validation::Invalid(e) => Validation::Invalid(e),
+        }
+    }
+
+    fn map2<A, B, C, G>(fa: Validation<E, A>, fb: Validation<E, B>, g: G) -> Validation<E, C>
+    where
+        G: FnOnce(A, B) -> C,
+    {
+        match (fa, fb) {
+            (Validation::Valid(a), Validation::Valid(b)) => Validation::Valid(g(a, b)),
+            (Validation::Invalid(a), Validation::Invalid(b)) => {
+                Validation::Invalid(E::append(a, b))
+            }
+            (Validation::Invalid(e), _) | (_, Validation::Invalid(e)) => Validation::Invalid(e),
+        }
+    }
+}
+
+pub trait Traversable: Sized {
+    type Item;
+    /// The same structure holding `B`s instead.
+    type Output<B>;
+
+    /// Maps every item to an effect and combines the effects in order.
+    ///
+    /// `f` is called for every item even if the effect short-circuits, use the iterator
+    /// adapters for that.
+    fn traverse<F, B, G>(self, f: G) -> F::F<Self::Output<B>>
+    where
+        F: Applicative,
+        G: FnMut(Self::Item) -> F::F<B>;
+
+    /// Turns a structure of effects inside out.
+    fn sequence<F, B>(self) -> F::F<Self::Output<B>>
+    where
+        F: Applicative,
+        Self: Traversable<Item = F::F<B>>,
+    {
+        self.traverse::<F, B, _>(|x| x)
+    }
+}
+
+impl<A> Traversable for Vec<A> {
+    type Item = A;
+    type Output<B> = Vec<B>;
+
+    fn traverse<F, B, G>(self, mut f: G) -> F::F<Vec<B>>
+    where
+        F: Applicative,
+        G: FnMut(A) -> F::F<B>,
+    {
+        let len = self.len();
+        self.into_iter()
+            .fold(F::pure(Vec::with_capacity(len)), |acc, x| {
+                F::map2(acc, f(x), |mut acc, b| {
+                    acc.push(b);
+                    acc
+                })
+            })
+    }
+}
+
+impl<A> Traversable for Option<A> {
+    type Item = A;
+    type Output<B> = Option<B>;
+
+    fn traverse<F, B, G>(self, mut f: G) -> F::F<Option<B>>
+    where
+        F: Applicative,
+        G: FnMut(A) -> F::F<B>,
+    {
+        match self {
+            Some(a) => F::map(f(a), Some),
+            None => F::pure(None),
+        }
+    }
+}
+
+/// Traverses the `Ok` value, errors are passed through untouched.
+impl<A, E> Traversable for Result<A, E> {
+    type Item = A;
+    type Output<B> = Result<B, E>;
+
+    fn traverse<F, B, G>(self, mut f: G) -> F::F<Result<B, E>>
+    where
+        F: Applicative,
+        G: FnMut(A) -> F::F<B>,
+    {
+        match self {
+            Ok(a) => F::map(f(a), Ok),
+            Err(e) => F::pure(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::super::{laws::check_associative, laws::check_identity, *};
+    use super::*;
+
+    type Errors = VecAppend<String>;
+
+    #[quickcheck]
+    fn test_sequence_option(xs: Vec<Option<u8>>) {
+        assert_eq!(
+            xs.clone().sequence::<OptionApplicative, _>(),
+            xs.into_iter().collect::<Option<Vec<_>>>()
+        );
+    }
+
+    #[quickcheck]
+    fn test_sequence_result(xs: Vec<Result<u8, String>>) {
+        assert_eq!(
+            xs.clone().sequence::<ResultApplicative<String>, _>(),
+            xs.into_iter().collect::<Result<Vec<_>, _>>()
+        );
+    }
+
+    #[quickcheck]
+    fn test_traverse_validation(xs: Vec<Result<u8, String>>) {
+        let validated = xs
+            .clone()
+            .traverse::<ValidationApplicative<Errors>, _, _>(|x| match x {
+                Ok(x) => Validation::Valid(x),
+                Err(e) => Validation::Invalid(vec![e]),
+            });
+        let errors = xs
+            .iter()
+            .filter_map(|x| x.clone().err())
+            .collect::<Vec<_>>();
+        if errors.is_empty() {
+            assert_eq!(
+                validated,
+                Validation::Valid(xs.into_iter().map(Result::unwrap).collect())
+            );
+        } else {
+            assert_eq!(validated, Validation::Invalid(errors));
+        }
+    }
+
+    #[quickcheck]
+    fn test_traverse_option(x: Option<u8>, y: Option<u8>) {
+        let f = |x: u8| y.map(|y| x as u16 + y as u16);
+        assert_eq!(
+            x.traverse::<OptionApplicative, _, _>(f),
+            match x {
+                Some(x) => f(x).map(Some),
+                None => Some(None),
+            }
+        );
+    }
+
+    #[quickcheck]
+    fn test_traverse_result(x: Result<u8, String>) {
+        let halve = |x: u8| {
+            if x.is_multiple_of(2) {
+                Some(x / 2)
+            } else {
+                None
+            }
+        };
+        assert_eq!(
+            x.clone().traverse::<OptionApplicative, _, _>(halve),
+            match x {
+                Ok(x) => halve(x).map(Ok),
+                Err(e) => Some(Err(e)),
+            }
+        );
+    }
+
+    #[quickcheck]
+    fn test_validation_monoid(
+        a: Result<String, Vec<String>>,
+        b: Result<String, Vec<String>>,
+        c: Result<String, Vec<String>>,
+    ) {
+        type M = Validation<Errors, StringAppend>;
+        check_identity::<M>(a.clone().into());
+        check_associative::<M>(a.into(), b.into(), c.into());
+    }
+
+    #[test]
+    fn test_validate_form() {
+        fn parse_age(s: &str) -> Validation<Errors, u8> {
+            s.parse()
+                .map_err(|_| vec![format!("invalid age {s:?}")])
+                .into()
+        }
+
+        let ok = vec!["42", "7"].traverse::<ValidationApplicative<Errors>, _, _>(parse_age);
+        assert_eq!(ok.into_result(), Ok(vec![42, 7]));
+
+        let err = vec!["x", "7", "-1"].traverse::<ValidationApplicative<Errors>, _, _>(parse_age);
+        assert_eq!(
+            err.into_result(),
+            Err(vec![
+                "invalid age \"x\"".to_string(),
+                "invalid age \"-1\"".to_string()
+            ])
+        );
+
+        // the short-circuiting effects only keep the first error
+        let first = vec!["x", "7", "-1"]
+            .traverse::<ResultApplicative<_>, _, _>(|s| s.parse::<u8>().map_err(|_| s));
+        assert_eq!(first, Err("x"));
+    }
+}
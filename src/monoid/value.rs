@@ -0,0 +1,254 @@
+//! Value-level monoids: newtypes that know their own operation.
+//!
+//! The tag types (`Add`, `Max`, ...) keep the operation apart from the value, which is handy for
+//! picking an operation for an existing type, but means `3 + 4` can't be written with them. The
+//! wrappers here go the other way: `Sum(3) + Sum(4) == Sum(7)`, `Default` is the identity and
+//! `std::iter::Sum` accumulates. Each of them is also its own tag (`Sum::<u8>::accumulate`)
+//! and `Value<M>` wraps any tag based monoid the same way.
+
+use std::{iter, ops};
+
+use num_traits::{One, Zero};
+
+use super::{Bounded, Monoid, Semigroup};
+
+macro_rules! value_monoid {
+    ($name:ident<$t:ident>, $($bound:path),+) => {
+        impl<$t: $($bound +)+> Semigroup for $name<$t> {
+            type T = Self;
+
+            fn append(a: Self, b: Self) -> Self {
+                a + b
+            }
+        }
+        impl<$t: $($bound +)+> Monoid for $name<$t> {
+            fn identity() -> Self {
+                Self::default()
+            }
+        }
+        impl<$t: $($bound +)+> iter::Sum for $name<$t> {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::default(), ops::Add::add)
+            }
+        }
+    };
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct Sum<T>(pub T);
+impl<T: Zero> Default for Sum<T> {
+    fn default() -> Self {
+        Self(T::zero())
+    }
+}
+impl<T: Zero> ops::Add for Sum<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+value_monoid!(Sum<T>, Zero);
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct Product<T>(pub T);
+impl<T: One> Default for Product<T> {
+    fn default() -> Self {
+        Self(T::one())
+    }
+}
+impl<T: One> ops::Add for Product<T> {
+    type Output = Self;
+
+    // `+` is the monoid operation, which for `Product` is multiplication
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 * other.0)
+    }
+}
+value_monoid!(Product<T>, One);
+
+/// Biggest value, like the `Max` tag it takes floats too but is only lawful without NaNs.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct MaxOf<T>(pub T);
+impl<T: Bounded> Default for MaxOf<T> {
+    fn default() -> Self {
+        Self(T::MIN)
+    }
+}
+impl<T: PartialOrd> ops::Add for MaxOf<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        if other.0 > self.0 {
+            other
+        } else {
+            self
+        }
+    }
+}
+value_monoid!(MaxOf<T>, PartialOrd, Bounded);
+
+/// First value that's there (`First(None)` is the identity).
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct First<T>(pub Option<T>);
+impl<T> Default for First<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+impl<T> ops::Add for First<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0.or(other.0))
+    }
+}
+value_monoid!(First<T>, Sized);
+
+/// Last value that's there (`Last(None)` is the identity).
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct Last<T>(pub Option<T>);
+impl<T> Default for Last<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+impl<T> ops::Add for Last<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(other.0.or(self.0))
+    }
+}
+value_monoid!(Last<T>, Sized);
+
+/// Value of a tag based monoid `M` that remembers its operation.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(
+    transparent,
+    bound(
+        serialize = "M::T: serde::Serialize",
+        deserialize = "M::T: serde::Deserialize<'de>"
+    )
+)]
+pub struct Value<M: Monoid>(pub M::T);
+impl<M: Monoid> Value<M> {
+    pub fn into_inner(self) -> M::T {
+        self.0
+    }
+}
+impl<M: Monoid> Default for Value<M> {
+    fn default() -> Self {
+        Self(M::identity())
+    }
+}
+impl<M: Monoid> ops::Add for Value<M> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(M::append(self.0, other.0))
+    }
+}
+impl<M: Monoid> iter::Sum for Value<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(M::accumulate(iter.map(Self::into_inner)))
+    }
+}
+impl<M: Monoid> Clone for Value<M>
+where
+    M::T: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+impl<M: Monoid> std::fmt::Debug for Value<M>
+where
+    M::T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Value").field(&self.0).finish()
+    }
+}
+impl<M: Monoid> PartialEq for Value<M>
+where
+    M::T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::super::{laws::*, SaturatingAdd, StringAppend};
+    use super::*;
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(Sum(3) + Sum(4), Sum(7));
+        assert_eq!(Sum(1.5) + Sum(2.25), Sum(3.75));
+        assert_eq!(Product(3u8) + Product(4), Product(12));
+        assert_eq!(MaxOf('a') + MaxOf('z') + MaxOf('q'), MaxOf('z'));
+        assert_eq!(MaxOf(-1.5) + MaxOf(0.25) + MaxOf::default(), MaxOf(0.25));
+        assert_eq!(
+            First(None) + First(Some(1)) + First(Some(2)),
+            First(Some(1))
+        );
+        assert_eq!(Last(Some(1)) + Last(Some(2)) + Last(None), Last(Some(2)));
+    }
+
+    #[test]
+    fn test_iter_sum() {
+        assert_eq!([1i16, 2, 3].map(Sum).into_iter().sum::<Sum<_>>(), Sum(6));
+        assert_eq!(
+            [2.0f32, 0.5, 3.0]
+                .map(Product)
+                .into_iter()
+                .sum::<Product<_>>(),
+            Product(3.0)
+        );
+        assert_eq!(
+            Vec::<MaxOf<u64>>::new().into_iter().sum::<MaxOf<_>>(),
+            MaxOf(0)
+        );
+        assert_eq!(Sum::<u128>::default(), Sum(0));
+    }
+
+    #[quickcheck]
+    fn test_laws(a: i32, b: i32, c: i32) {
+        let (a, b, c) = (i64::from(a), i64::from(b), i64::from(c));
+        check_identity::<Sum<i64>>(Sum(a));
+        check_associative::<Sum<i64>>(Sum(a), Sum(b), Sum(c));
+        check_identity::<MaxOf<i64>>(MaxOf(a));
+        check_associative::<MaxOf<i64>>(MaxOf(a), MaxOf(b), MaxOf(c));
+        check_identity::<First<i64>>(First(Some(a)));
+        check_associative::<Last<i64>>(Last(Some(a)), Last(None), Last(Some(c)));
+    }
+
+    #[quickcheck]
+    fn test_value(xs: Vec<(u32, String)>) {
+        type M = (SaturatingAdd, StringAppend);
+        let total = xs
+            .iter()
+            .map(|(n, s)| Value::<M>((*n as usize, s.clone())))
+            .sum::<Value<M>>();
+        assert_eq!(
+            total.into_inner(),
+            M::accumulate(xs.into_iter().map(|(n, s)| (n as usize, s)))
+        );
+    }
+}
@@ -0,0 +1,384 @@
+//! Streaming aggregation over windows of the most recent values.
+//!
+//! Removing a value from an accumulation needs a `Group`, but most interesting monoids (`Max`,
+//! `StringAppend`, the sketches in `stats`) have no inverses. `WindowedAggregator` is a queue that
+//! keeps partial accumulations in two stacks instead, so pushing, popping and querying are all
+//! `O(1)` amortised appends for any `Monoid`.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use super::Monoid;
+
+/// FIFO queue of monoid values which can accumulate all of its values at any time.
+pub struct WindowedAggregator<M: Monoid> {
+    /// Oldest value on top, each with the accumulation of itself and everything below it (newer).
+    front: Vec<(M::T, M::T)>,
+    /// Newest value on top.
+    back: Vec<M::T>,
+    back_acc: M::T,
+}
+impl<M> WindowedAggregator<M>
+where
+    M: Monoid,
+    M::T: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            front: Vec::new(),
+            back: Vec::new(),
+            back_acc: M::identity(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, value: M::T) {
+        self.back_acc = M::append(self.back_acc.clone(), value.clone());
+        self.back.push(value);
+    }
+
+    /// Removes the oldest value.
+    pub fn pop(&mut self) -> Option<M::T> {
+        if self.front.is_empty() {
+            // reverse the back stack once, every value is moved at most once
+            for value in self.back.drain(..).rev() {
+                let acc = match self.front.last() {
+                    Some((_, acc)) => M::append(value.clone(), acc.clone()),
+                    None => value.clone(),
+                };
+                self.front.push((value, acc));
+            }
+            self.back_acc = M::identity();
+        }
+        self.front.pop().map(|(value, _)| value)
+    }
+
+    /// Accumulation of all values from oldest to newest.
+    pub fn query(&self) -> M::T {
+        match self.front.last() {
+            Some((_, acc)) => M::append(acc.clone(), self.back_acc.clone()),
+            None => self.back_acc.clone(),
+        }
+    }
+}
+impl<M> Default for WindowedAggregator<M>
+where
+    M: Monoid,
+    M::T: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Accumulation of the last `size` values.
+pub struct SlidingWindow<M: Monoid> {
+    size: usize,
+    values: WindowedAggregator<M>,
+}
+impl<M> SlidingWindow<M>
+where
+    M: Monoid,
+    M::T: Clone,
+{
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "window must not be empty");
+        Self {
+            size,
+            values: WindowedAggregator::new(),
+        }
+    }
+
+    /// Adds a value and returns the accumulation of the window ending with it.
+    pub fn push(&mut self, value: M::T) -> M::T {
+        if self.values.len() == self.size {
+            self.values.pop();
+        }
+        self.values.push(value);
+        self.values.query()
+    }
+}
+
+/// Accumulation of consecutive, non-overlapping chunks of `size` values.
+pub struct TumblingWindow<M: Monoid> {
+    size: usize,
+    len: usize,
+    acc: M::T,
+}
+impl<M: Monoid> TumblingWindow<M> {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "window must not be empty");
+        Self {
+            size,
+            len: 0,
+            acc: M::identity(),
+        }
+    }
+
+    /// Adds a value and returns the accumulation of the window it completed, if any.
+    pub fn push(&mut self, value: M::T) -> Option<M::T> {
+        self.acc = M::append(std::mem::replace(&mut self.acc, M::identity()), value);
+        self.len += 1;
+        if self.len == self.size {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the accumulation of the incomplete window, if it has any values.
+    pub fn flush(&mut self) -> Option<M::T> {
+        let len = std::mem::take(&mut self.len);
+        let acc = std::mem::replace(&mut self.acc, M::identity());
+        (len > 0).then_some(acc)
+    }
+}
+
+/// Accumulation of the values pushed in the last `width` of time.
+///
+/// Values have to be pushed in order of time.
+pub struct TimeSlidingWindow<M: Monoid> {
+    width: Duration,
+    times: VecDeque<Instant>,
+    values: WindowedAggregator<M>,
+}
+impl<M> TimeSlidingWindow<M>
+where
+    M: Monoid,
+    M::T: Clone,
+{
+    pub fn new(width: Duration) -> Self {
+        Self {
+            width,
+            times: VecDeque::new(),
+            values: WindowedAggregator::new(),
+        }
+    }
+
+    pub fn push(&mut self, at: Instant, value: M::T) {
+        assert!(
+            self.times.back().is_none_or(|&last| last <= at),
+            "values must be pushed in order"
+        );
+        self.times.push_back(at);
+        self.values.push(value);
+    }
+
+    /// Accumulation of the values pushed in `(now - width, now]`, older values are dropped.
+    pub fn query(&mut self, now: Instant) -> M::T {
+        while let Some(&oldest) = self.times.front() {
+            if now.saturating_duration_since(oldest) < self.width {
+                break;
+            }
+            self.times.pop_front();
+            self.values.pop();
+        }
+        self.values.query()
+    }
+}
+
+/// Accumulation of the values pushed in consecutive, non-overlapping intervals of `width`
+/// starting at `start`.
+///
+/// Values have to be pushed in order of time.
+pub struct TimeTumblingWindow<M: Monoid> {
+    width: Duration,
+    /// Start of the current window.
+    start: Instant,
+    acc: Option<M::T>,
+}
+impl<M: Monoid> TimeTumblingWindow<M> {
+    pub fn new(start: Instant, width: Duration) -> Self {
+        assert!(!width.is_zero(), "window must not be empty");
+        Self {
+            width,
+            start,
+            acc: None,
+        }
+    }
+
+    /// Adds a value and returns the start and accumulation of the window it closed, if any.
+    ///
+    /// Windows without any values are skipped.
+    pub fn push(&mut self, at: Instant, value: M::T) -> Option<(Instant, M::T)> {
+        assert!(at >= self.start, "values must be pushed in order");
+        let mut closed = None;
+        let elapsed = at.duration_since(self.start);
+        if elapsed >= self.width {
+            closed = self.flush();
+            // in nanoseconds as the number of windows can be more than the `u32` of `Duration * n`
+            let width = self.width.as_nanos();
+            self.start += duration_from_nanos(elapsed.as_nanos() / width * width);
+        }
+        self.acc = Some(match self.acc.take() {
+            Some(acc) => M::append(acc, value),
+            None => value,
+        });
+        closed
+    }
+
+    /// Returns the start and accumulation of the current window, if it has any values.
+    pub fn flush(&mut self) -> Option<(Instant, M::T)> {
+        self.acc.take().map(|acc| (self.start, acc))
+    }
+}
+
+/// Exact for anything up to `Duration::MAX`, unlike `Duration::from_nanos` which takes a `u64`.
+fn duration_from_nanos(nanos: u128) -> Duration {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+    Duration::new(
+        (nanos / NANOS_PER_SEC) as u64,
+        (nanos % NANOS_PER_SEC) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck_macros::quickcheck;
+
+    use super::super::*;
+    use super::*;
+
+    #[quickcheck]
+    fn test_aggregator(ops: Vec<Option<String>>) {
+        let mut aggregator = WindowedAggregator::<StringAppend>::new();
+        let mut naive = VecDeque::new();
+        for op in ops {
+            match op {
+                Some(value) => {
+                    aggregator.push(value.clone());
+                    naive.push_back(value);
+                }
+                None => assert_eq!(aggregator.pop(), naive.pop_front()),
+            }
+            assert_eq!(aggregator.len(), naive.len());
+            assert_eq!(
+                aggregator.query(),
+                StringAppend::accumulate(naive.iter().cloned())
+            );
+        }
+    }
+
+    #[quickcheck]
+    fn test_sliding_window(xs: Vec<String>, size: usize) {
+        let size = size % 8 + 1;
+        let mut window = SlidingWindow::<StringAppend>::new(size);
+        for (i, x) in xs.iter().enumerate() {
+            assert_eq!(
+                window.push(x.clone()),
+                xs[(i + 1).saturating_sub(size)..=i].concat()
+            );
+        }
+    }
+
+    #[quickcheck]
+    fn test_sliding_window_max(xs: Vec<u8>, size: usize) {
+        let size = size % 8 + 1;
+        let mut window = SlidingWindow::<Max<u8>>::new(size);
+        for (i, &x) in xs.iter().enumerate() {
+            let naive = xs[(i + 1).saturating_sub(size)..=i].iter().max();
+            assert_eq!(Some(window.push(x)), naive.copied());
+        }
+    }
+
+    #[quickcheck]
+    fn test_tumbling_window(xs: Vec<String>, size: usize) {
+        let size = size % 8 + 1;
+        let mut window = TumblingWindow::<StringAppend>::new(size);
+        let mut result = xs
+            .iter()
+            .filter_map(|x| window.push(x.clone()))
+            .collect::<Vec<_>>();
+        result.extend(window.flush());
+        assert_eq!(
+            result,
+            xs.chunks(size)
+                .map(|chunk| chunk.concat())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Turns offsets into sorted `Instant`s in `[0, 1s)` after some base.
+    fn instants(offsets: Vec<(u16, u8)>) -> (Instant, Vec<(Instant, u8)>) {
+        let base = Instant::now();
+        let mut values = offsets
+            .into_iter()
+            .map(|(ms, x)| (base + Duration::from_millis(ms as u64 % 1000), x))
+            .collect::<Vec<_>>();
+        values.sort_by_key(|&(at, _)| at);
+        (base, values)
+    }
+
+    #[quickcheck]
+    fn test_time_sliding_window(offsets: Vec<(u16, u8)>, width: u16) {
+        let width = Duration::from_millis(width as u64 % 200 + 1);
+        let (_, values) = instants(offsets);
+        let mut window = TimeSlidingWindow::<(SaturatingAdd, Max<u8>)>::new(width);
+        for (i, &(at, x)) in values.iter().enumerate() {
+            window.push(at, (x as usize, x));
+            let naive = <(SaturatingAdd, Max<u8>)>::accumulate(
+                values[..=i]
+                    .iter()
+                    .filter(|&&(t, _)| at - t < width)
+                    .map(|&(_, x)| (x as usize, x)),
+            );
+            assert_eq!(window.query(at), naive);
+        }
+    }
+
+    #[quickcheck]
+    fn test_time_tumbling_window(offsets: Vec<(u16, u8)>, width: u16) {
+        let width = Duration::from_millis(width as u64 % 200 + 1);
+        let (base, values) = instants(offsets);
+        let mut window = TimeTumblingWindow::<VecAppend<u8>>::new(base, width);
+        let mut result = values
+            .iter()
+            .filter_map(|&(at, x)| window.push(at, vec![x]))
+            .collect::<Vec<_>>();
+        result.extend(window.flush());
+
+        let mut naive = BTreeMap::<u128, Vec<u8>>::new();
+        for &(at, x) in &values {
+            let window = (at - base).as_nanos() / width.as_nanos();
+            naive.entry(window).or_default().push(x);
+        }
+        let naive = naive
+            .into_iter()
+            .map(|(window, xs)| (base + width * window as u32, xs))
+            .collect::<Vec<_>>();
+        assert_eq!(result, naive);
+    }
+
+    #[test]
+    fn test_time_sliding_window_expires() {
+        let start = Instant::now();
+        let mut window = TimeSlidingWindow::<SaturatingAdd>::new(Duration::from_secs(1));
+        window.push(start, 1);
+        window.push(start + Duration::from_millis(500), 2);
+        assert_eq!(window.query(start + Duration::from_millis(900)), 3);
+        assert_eq!(window.query(start + Duration::from_millis(1200)), 2);
+        assert_eq!(window.query(start + Duration::from_secs(5)), 0);
+    }
+
+    #[test]
+    fn test_time_tumbling_window_many_windows() {
+        // more than `u32::MAX` windows of 3µs between the two values
+        let start = Instant::now();
+        let width = Duration::from_micros(3);
+        let mut window = TimeTumblingWindow::<SaturatingAdd>::new(start, width);
+        let gap = Duration::from_secs(4 * 3600) + Duration::from_nanos(4_500);
+        assert_eq!(window.push(start, 1), None);
+        assert_eq!(window.push(start + gap, 2), Some((start, 1)));
+        let expected = start + Duration::from_secs(4 * 3600) + Duration::from_micros(3);
+        assert_eq!(window.flush(), Some((expected, 2)));
+    }
+}
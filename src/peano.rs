@@ -0,0 +1,384 @@
+//! Natural numbers as types, arithmetic is done by the trait solver.
+//!
+//! Every operation is a trait with an `Output` type and a type alias to use it, e.g.
+//! `Add<A, B> = <A as CalculateAdd<B>>::Output`. Operations that aren't defined (subtracting a
+//! bigger number, dividing by zero) simply have no impl, so using them is a compile error.
+
+use std::marker::PhantomData;
+
+use crate::{
+    boolean::{self, Bool, False, True},
+    type_list::{ConstSum, ConstUsize},
+};
+
+pub struct Zero;
+pub struct Succ<N>(PhantomData<N>);
+
+pub trait Value {
+    const VALUE: usize;
+}
+impl Value for Zero {
+    const VALUE: usize = 0;
+}
+impl<N> Value for Succ<N>
+where
+    N: Value,
+{
+    const VALUE: usize = 1 + <N as Value>::VALUE;
+}
+
+pub trait CalculateAdd<Num> {
+    type Output;
+}
+impl<N> CalculateAdd<N> for Zero {
+    type Output = N;
+}
+impl<N, M> CalculateAdd<N> for Succ<M>
+where
+    M: CalculateAdd<N>,
+{
+    type Output = Succ<<M as CalculateAdd<N>>::Output>;
+}
+pub type Add<A, B> = <A as CalculateAdd<B>>::Output;
+pub type Double<A> = Add<A, A>;
+
+pub trait CalculateMul<Num> {
+    type Output;
+}
+impl<N> CalculateMul<N> for Zero {
+    type Output = Zero;
+}
+impl<N, M> CalculateMul<N> for Succ<M>
+where
+    M: CalculateMul<N>,
+    N: CalculateAdd<Mul<M, N>>,
+{
+    type Output = Add<N, Mul<M, N>>;
+}
+pub type Mul<A, B> = <A as CalculateMul<B>>::Output;
+
+/// Subtraction that only exists if the result is a natural number.
+#[diagnostic::on_unimplemented(
+    message = "subtracting a bigger number from a smaller one",
+    label = "the result would be negative"
+)]
+pub trait CalculateSub<Num> {
+    type Output;
+}
+impl<N> CalculateSub<Zero> for N {
+    type Output = N;
+}
+impl<N, M> CalculateSub<Succ<M>> for Succ<N>
+where
+    N: CalculateSub<M>,
+{
+    type Output = Sub<N, M>;
+}
+pub type Sub<A, B> = <A as CalculateSub<B>>::Output;
+
+/// Subtraction that stops at `Zero`.
+pub trait CalculateSaturatingSub<Num> {
+    type Output;
+}
+impl<M> CalculateSaturatingSub<M> for Zero {
+    type Output = Zero;
+}
+impl<N> CalculateSaturatingSub<Zero> for Succ<N> {
+    type Output = Succ<N>;
+}
+impl<N, M> CalculateSaturatingSub<Succ<M>> for Succ<N>
+where
+    N: CalculateSaturatingSub<M>,
+{
+    type Output = SaturatingSub<N, M>;
+}
+pub type SaturatingSub<A, B> = <A as CalculateSaturatingSub<B>>::Output;
+
+// Type level `std::cmp::Ordering`
+pub struct Less;
+pub struct Equal;
+pub struct Greater;
+
+pub trait Ordering {
+    const ORDERING: std::cmp::Ordering;
+    /// The smaller of `A` and `B` if `A` compares to `B` as `Self`.
+    type Min<A, B>;
+    /// The bigger of `A` and `B` if `A` compares to `B` as `Self`.
+    type Max<A, B>;
+}
+impl Ordering for Less {
+    const ORDERING: std::cmp::Ordering = std::cmp::Ordering::Less;
+    type Min<A, B> = A;
+    type Max<A, B> = B;
+}
+impl Ordering for Equal {
+    const ORDERING: std::cmp::Ordering = std::cmp::Ordering::Equal;
+    type Min<A, B> = A;
+    type Max<A, B> = A;
+}
+impl Ordering for Greater {
+    const ORDERING: std::cmp::Ordering = std::cmp::Ordering::Greater;
+    type Min<A, B> = B;
+    type Max<A, B> = A;
+}
+
+pub trait Compare<Num> {
+    type Output: Ordering;
+}
+impl Compare<Zero> for Zero {
+    type Output = Equal;
+}
+impl<M> Compare<Succ<M>> for Zero {
+    type Output = Less;
+}
+impl<N> Compare<Zero> for Succ<N> {
+    type Output = Greater;
+}
+impl<N, M> Compare<Succ<M>> for Succ<N>
+where
+    N: Compare<M>,
+{
+    type Output = Cmp<N, M>;
+}
+pub type Cmp<A, B> = <A as Compare<B>>::Output;
+
+pub type Min<A, B> = <Cmp<A, B> as Ordering>::Min<A, B>;
+pub type Max<A, B> = <Cmp<A, B> as Ordering>::Max<A, B>;
+
+/// Euclidean division, only implemented for divisors other than `Zero`.
+#[diagnostic::on_unimplemented(message = "dividing by zero", label = "the divisor is `Zero`")]
+pub trait CalculateDivRem<Num> {
+    type Quotient;
+    type Remainder;
+}
+impl<N, M> CalculateDivRem<Succ<M>> for N
+where
+    N: Compare<Succ<M>>,
+    N: DivRemOrdered<Succ<M>, Cmp<N, Succ<M>>>,
+{
+    type Quotient = <N as DivRemOrdered<Succ<M>, Cmp<N, Succ<M>>>>::Quotient;
+    type Remainder = <N as DivRemOrdered<Succ<M>, Cmp<N, Succ<M>>>>::Remainder;
+}
+pub type Div<A, B> = <A as CalculateDivRem<B>>::Quotient;
+pub type Rem<A, B> = <A as CalculateDivRem<B>>::Remainder;
+
+/// Recursion helper for `CalculateDivRem` branching on how the dividend compares to the divisor.
+pub trait DivRemOrdered<Num, O> {
+    type Quotient;
+    type Remainder;
+}
+impl<N, M> DivRemOrdered<M, Less> for N {
+    type Quotient = Zero;
+    type Remainder = N;
+}
+impl<N, M> DivRemOrdered<M, Equal> for N
+where
+    N: CalculateSub<M>,
+    Sub<N, M>: CalculateDivRem<M>,
+{
+    type Quotient = Succ<Div<Sub<N, M>, M>>;
+    type Remainder = Rem<Sub<N, M>, M>;
+}
+impl<N, M> DivRemOrdered<M, Greater> for N
+where
+    N: CalculateSub<M>,
+    Sub<N, M>: CalculateDivRem<M>,
+{
+    type Quotient = Succ<Div<Sub<N, M>, M>>;
+    type Remainder = Rem<Sub<N, M>, M>;
+}
+
+pub trait CalculateIsZero {
+    type Output: Bool;
+}
+impl CalculateIsZero for Zero {
+    type Output = True;
+}
+impl<N> CalculateIsZero for Succ<N> {
+    type Output = False;
+}
+pub type IsZero<N> = <N as CalculateIsZero>::Output;
+
+pub trait CalculateIsEven {
+    type Output: Bool;
+}
+impl CalculateIsEven for Zero {
+    type Output = True;
+}
+impl<N: CalculateIsEven> CalculateIsEven for Succ<N> {
+    type Output = boolean::Not<IsEven<N>>;
+}
+pub type IsEven<N> = <N as CalculateIsEven>::Output;
+
+/// Conversion from other representations of naturals, e.g. `binary` numbers or const generics.
+pub trait ToPeano {
+    type Output;
+}
+impl ToPeano for Zero {
+    type Output = Zero;
+}
+impl<N> ToPeano for Succ<N> {
+    type Output = Succ<N>;
+}
+pub type Peano<N> = <N as ToPeano>::Output;
+
+// Bridge to const generics through `ConstUsize<N>`. The way back is just `Value::VALUE`, but
+// `Succ<Peano<ConstUsize<{ N - 1 }>>>` needs `generic_const_exprs` for a generic `N`, so every
+// `N` up to `MAX_CONST_USIZE` gets its own impl.
+impl<const N: usize> Value for ConstUsize<N> {
+    const VALUE: usize = N;
+}
+
+/// The biggest `N` with a `ToPeano` impl for `ConstUsize<N>`.
+pub const MAX_CONST_USIZE: usize = 64;
+
+macro_rules! impl_const_usize_to_peano {
+    ($($n:literal) *) => {
+        $(
+            impl ToPeano for ConstUsize<$n> {
+                type Output = Succ<Peano<ConstUsize<{ $n - 1 }>>>;
+            }
+        )*
+    };
+}
+impl ToPeano for ConstUsize<0> {
+    type Output = Zero;
+}
+impl_const_usize_to_peano!(
+    1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 33 34
+    35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63 64
+);
+
+impl ConstSum for Zero {
+    const SUM: usize = 0;
+}
+impl<N: Value> ConstSum for Succ<N> {
+    const SUM: usize = Self::VALUE;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type One = Succ<Zero>;
+    type Two = Succ<One>;
+    type Three = Succ<Two>;
+    type Four = Succ<Three>;
+    type Five = Succ<Four>;
+    type Seven = Add<Four, Three>;
+
+    #[test]
+    fn test_add() {
+        let two_plus_three: usize = <Add<Two, Three> as Value>::VALUE;
+        assert_eq!(two_plus_three, 5);
+        let one_plus_four: usize = <Add<One, Four> as Value>::VALUE;
+        assert_eq!(one_plus_four, 5);
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(<Mul<Two, Three> as Value>::VALUE, 6);
+        assert_eq!(<Mul<Five, Four> as Value>::VALUE, 20);
+        assert_eq!(<Mul<Zero, Four> as Value>::VALUE, 0);
+        assert_eq!(<Mul<Four, Zero> as Value>::VALUE, 0);
+        assert_eq!(<Mul<One, Seven> as Value>::VALUE, 7);
+    }
+
+    #[test]
+    fn test_sub() {
+        assert_eq!(<Sub<Five, Three> as Value>::VALUE, 2);
+        assert_eq!(<Sub<Five, Five> as Value>::VALUE, 0);
+        assert_eq!(<Sub<Four, Zero> as Value>::VALUE, 4);
+
+        assert_eq!(<SaturatingSub<Five, Three> as Value>::VALUE, 2);
+        assert_eq!(<SaturatingSub<Three, Five> as Value>::VALUE, 0);
+        assert_eq!(<SaturatingSub<Zero, Two> as Value>::VALUE, 0);
+        assert_eq!(<SaturatingSub<Two, Zero> as Value>::VALUE, 2);
+    }
+
+    #[test]
+    fn test_cmp() {
+        use std::cmp::Ordering::*;
+
+        assert_eq!(<Cmp<Two, Five> as Ordering>::ORDERING, Less);
+        assert_eq!(<Cmp<Four, Four> as Ordering>::ORDERING, Equal);
+        assert_eq!(<Cmp<Seven, Zero> as Ordering>::ORDERING, Greater);
+        assert_eq!(<Cmp<Zero, Zero> as Ordering>::ORDERING, Equal);
+    }
+
+    #[test]
+    fn test_min_max() {
+        assert_eq!(<Min<Two, Five> as Value>::VALUE, 2);
+        assert_eq!(<Min<Five, Two> as Value>::VALUE, 2);
+        assert_eq!(<Max<Two, Five> as Value>::VALUE, 5);
+        assert_eq!(<Max<Five, Two> as Value>::VALUE, 5);
+        assert_eq!(<Max<Three, Three> as Value>::VALUE, 3);
+    }
+
+    #[test]
+    fn test_div_rem() {
+        assert_eq!(<Div<Seven, Two> as Value>::VALUE, 3);
+        assert_eq!(<Rem<Seven, Two> as Value>::VALUE, 1);
+        assert_eq!(<Div<Mul<Four, Five>, Five> as Value>::VALUE, 4);
+        assert_eq!(<Rem<Mul<Four, Five>, Five> as Value>::VALUE, 0);
+        assert_eq!(<Div<Two, Five> as Value>::VALUE, 0);
+        assert_eq!(<Rem<Two, Five> as Value>::VALUE, 2);
+        assert_eq!(<Div<Zero, One> as Value>::VALUE, 0);
+    }
+
+    #[test]
+    fn test_predicates() {
+        const _: () = assert!(<IsZero<Zero> as Bool>::VALUE);
+        const _: () = assert!(!<IsZero<Three> as Bool>::VALUE);
+        const _: () = assert!(<IsEven<Mul<Three, Four>> as Bool>::VALUE);
+        const _: () = assert!(!<IsEven<Seven> as Bool>::VALUE);
+
+        crate::assert_type_eq::<IsEven<Zero>, True>();
+        crate::assert_type_eq::<IsEven<Five>, False>();
+        crate::assert_type_eq::<boolean::And<IsEven<Four>, IsZero<Four>>, False>();
+        crate::assert_type_eq::<boolean::If<IsZero<Zero>, One, Two>, One>();
+        crate::assert_type_eq::<boolean::If<IsZero<Three>, Zero, Sub<Three, One>>, Two>();
+    }
+
+    #[test]
+    fn test_const_generics() {
+        assert_eq!(<Peano<ConstUsize<0>> as Value>::VALUE, 0);
+        assert_eq!(<Peano<ConstUsize<7>> as Value>::VALUE, 7);
+        assert_eq!(
+            <Peano<ConstUsize<MAX_CONST_USIZE>> as Value>::VALUE,
+            MAX_CONST_USIZE
+        );
+        crate::assert_type_eq::<Peano<ConstUsize<3>>, Three>();
+        crate::assert_type_eq::<Add<Peano<ConstUsize<4>>, Three>, Seven>();
+        assert_eq!(<ConstUsize<5> as Value>::VALUE, <Five as Value>::VALUE);
+
+        fn mul<const N: usize, const M: usize>() -> usize
+        where
+            ConstUsize<N>: ToPeano<Output: CalculateMul<Peano<ConstUsize<M>>>>,
+            ConstUsize<M>: ToPeano,
+            Mul<Peano<ConstUsize<N>>, Peano<ConstUsize<M>>>: Value,
+        {
+            <Mul<Peano<ConstUsize<N>>, Peano<ConstUsize<M>>> as Value>::VALUE
+        }
+        assert_eq!(mul::<6, 7>(), 42);
+    }
+
+    #[test]
+    fn test_macro() {
+        crate::assert_type_eq::<crate::peano!(0), Zero>();
+        crate::assert_type_eq::<crate::peano!(3), Three>();
+        crate::assert_type_eq::<crate::peano!(0x7), Seven>();
+        crate::assert_type_eq::<crate::peano!(0b101), Peano<ConstUsize<5>>>();
+        assert_eq!(<crate::peano!(100) as Value>::VALUE, 100);
+        assert_eq!(<crate::peano!(0x3f) as Value>::VALUE, 63);
+    }
+
+    #[test]
+    fn test_const_sum() {
+        use crate::type_list::{cons, const_sum, Cons, Nil};
+
+        type List = Cons<ConstUsize<1>, Cons<Three, Cons<Peano<ConstUsize<5>>, Nil>>>;
+        assert_eq!(<List as ConstSum>::SUM, 9);
+        assert_eq!(const_sum(&cons(ConstUsize::<2>, cons(Zero, Nil))), 2);
+    }
+}
@@ -0,0 +1,413 @@
+use crate::peano::{Succ, Zero};
+
+// List parts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Nil;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cons<H, T> {
+    pub head: H,
+    pub tail: T,
+}
+
+pub const fn cons<H, T>(head: H, tail: T) -> Cons<H, T> {
+    Cons { head, tail }
+}
+
+pub struct ConstUsize<const N: usize>;
+
+// List operations
+pub trait Len {
+    const LEN: usize;
+}
+pub const fn len<L: Len>(_: &L) -> usize {
+    L::LEN
+}
+
+impl Len for Nil {
+    const LEN: usize = 0;
+}
+impl<H, T> Len for Cons<H, T>
+where
+    T: Len,
+{
+    const LEN: usize = 1 + T::LEN;
+}
+
+pub trait Sum<T> {
+    fn sum(&self) -> T;
+}
+impl Sum<usize> for Nil {
+    fn sum(&self) -> usize {
+        0
+    }
+}
+impl<T> Sum<usize> for Cons<usize, T>
+where
+    T: Sum<usize>,
+{
+    fn sum(&self) -> usize {
+        self.head + self.tail.sum()
+    }
+}
+
+pub trait ConstSum {
+    const SUM: usize;
+}
+pub const fn const_sum<L: ConstSum>(_: &L) -> usize {
+    L::SUM
+}
+
+impl ConstSum for Nil {
+    const SUM: usize = 0;
+}
+impl<H, T> ConstSum for Cons<H, T>
+where
+    H: ConstSum,
+    T: ConstSum,
+{
+    const SUM: usize = H::SUM + T::SUM;
+}
+impl<const N: usize> ConstSum for ConstUsize<N> {
+    const SUM: usize = N;
+}
+
+pub trait Append<Rhs> {
+    type Output;
+
+    fn append(self, rhs: Rhs) -> Self::Output;
+}
+impl<Rhs> Append<Rhs> for Nil {
+    type Output = Rhs;
+
+    fn append(self, rhs: Rhs) -> Rhs {
+        rhs
+    }
+}
+impl<H, T, Rhs> Append<Rhs> for Cons<H, T>
+where
+    T: Append<Rhs>,
+{
+    type Output = Cons<H, T::Output>;
+
+    fn append(self, rhs: Rhs) -> Self::Output {
+        cons(self.head, self.tail.append(rhs))
+    }
+}
+
+pub trait Reverse {
+    type Output;
+
+    fn reverse(self) -> Self::Output;
+}
+impl<L> Reverse for L
+where
+    L: ReverseOnto<Nil>,
+{
+    type Output = L::Output;
+
+    fn reverse(self) -> Self::Output {
+        self.reverse_onto(Nil)
+    }
+}
+
+/// Recursion helper for `Reverse`, moves the items to the front of `Acc` one by one.
+pub trait ReverseOnto<Acc> {
+    type Output;
+
+    fn reverse_onto(self, acc: Acc) -> Self::Output;
+}
+impl<Acc> ReverseOnto<Acc> for Nil {
+    type Output = Acc;
+
+    fn reverse_onto(self, acc: Acc) -> Acc {
+        acc
+    }
+}
+impl<H, T, Acc> ReverseOnto<Acc> for Cons<H, T>
+where
+    T: ReverseOnto<Cons<H, Acc>>,
+{
+    type Output = T::Output;
+
+    fn reverse_onto(self, acc: Acc) -> Self::Output {
+        self.tail.reverse_onto(cons(self.head, acc))
+    }
+}
+
+/// The item at a `peano` index.
+pub trait Get<Index> {
+    type Output;
+
+    fn get(&self) -> &Self::Output;
+    fn get_mut(&mut self) -> &mut Self::Output;
+}
+impl<H, T> Get<Zero> for Cons<H, T> {
+    type Output = H;
+
+    fn get(&self) -> &H {
+        &self.head
+    }
+
+    fn get_mut(&mut self) -> &mut H {
+        &mut self.head
+    }
+}
+impl<H, T, I> Get<Succ<I>> for Cons<H, T>
+where
+    T: Get<I>,
+{
+    type Output = T::Output;
+
+    fn get(&self) -> &Self::Output {
+        self.tail.get()
+    }
+
+    fn get_mut(&mut self) -> &mut Self::Output {
+        self.tail.get_mut()
+    }
+}
+impl<H, T> Cons<H, T> {
+    /// `Get::get` with the index as a type parameter, e.g. `list.at::<Succ<Zero>>()`.
+    pub fn at<I>(&self) -> &<Self as Get<I>>::Output
+    where
+        Self: Get<I>,
+    {
+        Get::<I>::get(self)
+    }
+}
+
+/// A function that can be called with different types, which closures can't.
+pub trait Func<Input> {
+    type Output;
+
+    fn call(&mut self, input: Input) -> Self::Output;
+}
+
+pub trait Map<F> {
+    type Output;
+
+    fn map(self, f: &mut F) -> Self::Output;
+}
+impl<F> Map<F> for Nil {
+    type Output = Nil;
+
+    fn map(self, _: &mut F) -> Nil {
+        Nil
+    }
+}
+impl<H, T, F> Map<F> for Cons<H, T>
+where
+    F: Func<H>,
+    T: Map<F>,
+{
+    type Output = Cons<F::Output, T::Output>;
+
+    fn map(self, f: &mut F) -> Self::Output {
+        let head = f.call(self.head);
+        cons(head, self.tail.map(f))
+    }
+}
+
+/// Left fold, `F` is called with `(accumulator, item)` and returns the next accumulator.
+///
+/// Implemented on the list itself with the accumulator type as a parameter, so every step is
+/// picked by the concrete list type and its output is just the output of the rest of the list.
+/// Deriving the output from a folder type keyed on the tail like
+/// `<usize as Add<<SumFolder<T> as Fold<usize, T>>::Output>>::Output` instead sends the trait
+/// solver into infinite recursion before it knows `T`, see `test_fold_long_list`.
+pub trait Fold<Acc, F> {
+    type Output;
+
+    fn fold(self, acc: Acc, f: &mut F) -> Self::Output;
+}
+impl<Acc, F> Fold<Acc, F> for Nil {
+    type Output = Acc;
+
+    fn fold(self, acc: Acc, _: &mut F) -> Acc {
+        acc
+    }
+}
+impl<H, T, Acc, F> Fold<Acc, F> for Cons<H, T>
+where
+    F: Func<(Acc, H)>,
+    T: Fold<F::Output, F>,
+{
+    type Output = T::Output;
+
+    fn fold(self, acc: Acc, f: &mut F) -> Self::Output {
+        let acc = f.call((acc, self.head));
+        self.tail.fold(acc, f)
+    }
+}
+
+/// Creates a list of the given items, `hlist![1, "a", 3.0]` is
+/// `cons(1, cons("a", cons(3.0, Nil)))`.
+#[macro_export]
+macro_rules! hlist {
+    () => {
+        $crate::type_list::Nil
+    };
+    ($head:expr $(, $($tail:tt)*)?) => {
+        $crate::type_list::cons($head, $crate::hlist!($($($tail)*)?))
+    };
+}
+
+/// The type of a list with items of the given types, `HList![usize, &str]` is
+/// `Cons<usize, Cons<&str, Nil>>`.
+#[macro_export]
+macro_rules! HList {
+    () => {
+        $crate::type_list::Nil
+    };
+    ($head:ty $(, $($tail:tt)*)?) => {
+        $crate::type_list::Cons<$head, $crate::HList!($($($tail)*)?)>
+    };
+}
+
+/// A pattern destructuring a list item by item, e.g. `let hlist_pat![a, b] = hlist![1, 2];`.
+/// Ending it with `..` ignores the rest of the list.
+#[macro_export]
+macro_rules! hlist_pat {
+    () => {
+        $crate::type_list::Nil
+    };
+    (..) => {
+        _
+    };
+    ($head:pat $(, $($tail:tt)*)?) => {
+        $crate::type_list::Cons {
+            head: $head,
+            tail: $crate::hlist_pat!($($($tail)*)?),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Add;
+
+    use crate::peano::Peano;
+
+    use super::*;
+
+    type One = Succ<Zero>;
+    type Two = Succ<One>;
+
+    struct ToString;
+    impl<T: std::fmt::Display> Func<T> for ToString {
+        type Output = String;
+
+        fn call(&mut self, input: T) -> String {
+            input.to_string()
+        }
+    }
+
+    struct Plus;
+    impl<A: Add<B>, B> Func<(A, B)> for Plus {
+        type Output = A::Output;
+
+        fn call(&mut self, (a, b): (A, B)) -> A::Output {
+            a + b
+        }
+    }
+
+    /// Counts the calls, so `F` can have state.
+    struct Describe(usize);
+    impl<T: std::fmt::Debug> Func<(String, T)> for Describe {
+        type Output = String;
+
+        fn call(&mut self, (acc, item): (String, T)) -> String {
+            self.0 += 1;
+            format!("{acc}{}:{item:?} ", self.0)
+        }
+    }
+
+    #[test]
+    fn test_append_and_reverse() {
+        let list = cons(1, cons("two", Nil)).append(cons(3.0, Nil));
+        assert_eq!(list, cons(1, cons("two", cons(3.0, Nil))));
+        assert_eq!(Nil.append(Nil), Nil);
+
+        let reversed: Cons<f64, Cons<&str, Cons<i32, Nil>>> = list.reverse();
+        assert_eq!(reversed, cons(3.0, cons("two", cons(1, Nil))));
+        assert_eq!(reversed.reverse(), list);
+        assert_eq!(Nil.reverse(), Nil);
+    }
+
+    #[test]
+    fn test_get() {
+        let mut list = cons(1u8, cons("two", cons('3', Nil)));
+        assert_eq!(*list.at::<Zero>(), 1);
+        assert_eq!(*list.at::<One>(), "two");
+        assert_eq!(*list.at::<Peano<ConstUsize<2>>>(), '3');
+
+        *Get::<Two>::get_mut(&mut list) = 'c';
+        assert_eq!(list, cons(1, cons("two", cons('c', Nil))));
+    }
+
+    #[test]
+    fn test_map() {
+        let list = cons(1, cons("two", cons(3.5, Nil))).map(&mut ToString);
+        let strings = cons(
+            "1".to_string(),
+            cons("two".to_string(), cons("3.5".to_string(), Nil)),
+        );
+        assert_eq!(list, strings);
+        assert_eq!(Nil.map(&mut ToString), Nil);
+    }
+
+    #[test]
+    fn test_fold() {
+        let list = cons(1u8, cons("two", cons(Some('3'), Nil)));
+        let mut describe = Describe(0);
+        assert_eq!(
+            list.fold(String::new(), &mut describe),
+            "1:1 2:\"two\" 3:Some('3') "
+        );
+        assert_eq!(describe.0, 3);
+
+        let list = cons("a", cons("b", cons("c", Nil)));
+        assert_eq!(list.fold(String::new(), &mut Plus), "abc");
+        assert_eq!(Nil.fold(0, &mut Plus), 0);
+    }
+
+    /// The old `SumFolder` overflowed the trait solver here, even for three items: a generic `sum`
+    /// whose output goes through `Add` at every step, called on a list built in the call.
+    #[test]
+    fn test_fold_long_list() {
+        fn sum<L: Fold<usize, Plus>>(list: L) -> L::Output {
+            list.fold(0, &mut Plus)
+        }
+
+        assert_eq!(sum(cons(1, cons(2, cons(3, Nil)))), 6);
+        assert_eq!(
+            sum(crate::hlist![
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23,
+                24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44,
+                45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65,
+                66, 67, 68, 69, 70
+            ]),
+            70 * 71 / 2
+        );
+    }
+
+    #[test]
+    fn test_macros() {
+        let list: HList![u8, &str, f64] = hlist![1, "a", 3.0];
+        assert_eq!(list, cons(1, cons("a", cons(3.0, Nil))));
+        let _: HList![] = hlist![];
+        let _: HList![char,] = hlist!['a',];
+
+        let hlist_pat![a, b, c] = list;
+        assert_eq!((a, b, c), (1, "a", 3.0));
+        let hlist_pat![first, ..] = list;
+        assert_eq!(first, 1);
+        let hlist_pat![_, (x, y), ..] = hlist![(), (1, 'y'), "rest", "ignored"];
+        assert_eq!((x, y), (1, 'y'));
+
+        match hlist![Some(1), 2] {
+            hlist_pat![Some(n), m] => assert_eq!(n + m, 3),
+            hlist_pat![None, _] => unreachable!(),
+        }
+    }
+}
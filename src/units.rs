@@ -0,0 +1,288 @@
+//! Units of measure checked by the compiler.
+//!
+//! A `Quantity<V, D>` is just a `V` tagged with its dimension `D`, a `Dim` of the integer
+//! exponents of the seven SI base dimensions. Adding quantities needs the exact same dimension,
+//! multiplying and dividing them adds and subtracts the exponents, all at the type level so a
+//! `Quantity` costs nothing at runtime.
+//!
+//! ```
+//! use exploring_rust::units::*;
+//!
+//! let distance = 100.0 * METRE;
+//! let time = 9.58 * SECOND;
+//! let speed: Quantity<f64, Velocity> = distance / time;
+//! assert!((speed / (METRE / SECOND)).value() > 10.0);
+//! ```
+
+use std::{fmt, marker::PhantomData, ops};
+
+use crate::{
+    integer::{PosInt, Z0},
+    peano::{self, CalculateAdd, CalculateSub, Succ, Zero},
+};
+
+/// Exponents of length, mass, time, electric current, temperature, amount of substance and
+/// luminous intensity.
+pub struct Dim<L, M, T, I, Th, N, J>(PhantomData<(L, M, T, I, Th, N, J)>);
+
+pub trait DimMul<Rhs> {
+    type Output;
+}
+impl<L1, M1, T1, I1, Th1, N1, J1, L2, M2, T2, I2, Th2, N2, J2>
+    DimMul<Dim<L2, M2, T2, I2, Th2, N2, J2>> for Dim<L1, M1, T1, I1, Th1, N1, J1>
+where
+    L1: CalculateAdd<L2>,
+    M1: CalculateAdd<M2>,
+    T1: CalculateAdd<T2>,
+    I1: CalculateAdd<I2>,
+    Th1: CalculateAdd<Th2>,
+    N1: CalculateAdd<N2>,
+    J1: CalculateAdd<J2>,
+{
+    type Output = Dim<
+        peano::Add<L1, L2>,
+        peano::Add<M1, M2>,
+        peano::Add<T1, T2>,
+        peano::Add<I1, I2>,
+        peano::Add<Th1, Th2>,
+        peano::Add<N1, N2>,
+        peano::Add<J1, J2>,
+    >;
+}
+/// Dimension of the product of quantities with dimensions `A` and `B`.
+pub type Product<A, B> = <A as DimMul<B>>::Output;
+
+pub trait DimDiv<Rhs> {
+    type Output;
+}
+impl<L1, M1, T1, I1, Th1, N1, J1, L2, M2, T2, I2, Th2, N2, J2>
+    DimDiv<Dim<L2, M2, T2, I2, Th2, N2, J2>> for Dim<L1, M1, T1, I1, Th1, N1, J1>
+where
+    L1: CalculateSub<L2>,
+    M1: CalculateSub<M2>,
+    T1: CalculateSub<T2>,
+    I1: CalculateSub<I2>,
+    Th1: CalculateSub<Th2>,
+    N1: CalculateSub<N2>,
+    J1: CalculateSub<J2>,
+{
+    type Output = Dim<
+        peano::Sub<L1, L2>,
+        peano::Sub<M1, M2>,
+        peano::Sub<T1, T2>,
+        peano::Sub<I1, I2>,
+        peano::Sub<Th1, Th2>,
+        peano::Sub<N1, N2>,
+        peano::Sub<J1, J2>,
+    >;
+}
+/// Dimension of the quotient of quantities with dimensions `A` and `B`.
+pub type Quotient<A, B> = <A as DimDiv<B>>::Output;
+
+type P1 = PosInt<Succ<Zero>>;
+
+// Base dimensions
+pub type Dimensionless = Dim<Z0, Z0, Z0, Z0, Z0, Z0, Z0>;
+pub type Length = Dim<P1, Z0, Z0, Z0, Z0, Z0, Z0>;
+pub type Mass = Dim<Z0, P1, Z0, Z0, Z0, Z0, Z0>;
+pub type Time = Dim<Z0, Z0, P1, Z0, Z0, Z0, Z0>;
+pub type Current = Dim<Z0, Z0, Z0, P1, Z0, Z0, Z0>;
+pub type Temperature = Dim<Z0, Z0, Z0, Z0, P1, Z0, Z0>;
+pub type Amount = Dim<Z0, Z0, Z0, Z0, Z0, P1, Z0>;
+pub type LuminousIntensity = Dim<Z0, Z0, Z0, Z0, Z0, Z0, P1>;
+
+// Derived dimensions
+pub type Area = Product<Length, Length>;
+pub type Volume = Product<Area, Length>;
+pub type Frequency = Quotient<Dimensionless, Time>;
+pub type Velocity = Quotient<Length, Time>;
+pub type Acceleration = Quotient<Velocity, Time>;
+pub type Force = Product<Mass, Acceleration>;
+pub type Pressure = Quotient<Force, Area>;
+pub type Energy = Product<Force, Length>;
+pub type Power = Quotient<Energy, Time>;
+pub type Charge = Product<Current, Time>;
+pub type Voltage = Quotient<Power, Current>;
+pub type Resistance = Quotient<Voltage, Current>;
+
+#[repr(transparent)]
+pub struct Quantity<V, D> {
+    value: V,
+    dim: PhantomData<D>,
+}
+impl<V, D> Quantity<V, D> {
+    pub const fn new(value: V) -> Self {
+        Self {
+            value,
+            dim: PhantomData,
+        }
+    }
+
+    /// The number in SI units, divide by a unit first to get it in other units.
+    pub fn value(self) -> V {
+        self.value
+    }
+}
+impl<V: Clone, D> Clone for Quantity<V, D> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+impl<V: Copy, D> Copy for Quantity<V, D> {}
+impl<V: fmt::Debug, D> fmt::Debug for Quantity<V, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Quantity").field(&self.value).finish()
+    }
+}
+impl<V: PartialEq, D> PartialEq for Quantity<V, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+impl<V: PartialOrd, D> PartialOrd for Quantity<V, D> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<V: ops::Add, D> ops::Add for Quantity<V, D> {
+    type Output = Quantity<V::Output, D>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Quantity::new(self.value + rhs.value)
+    }
+}
+impl<V: ops::Sub, D> ops::Sub for Quantity<V, D> {
+    type Output = Quantity<V::Output, D>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Quantity::new(self.value - rhs.value)
+    }
+}
+impl<V: ops::Neg, D> ops::Neg for Quantity<V, D> {
+    type Output = Quantity<V::Output, D>;
+
+    fn neg(self) -> Self::Output {
+        Quantity::new(-self.value)
+    }
+}
+impl<V1, D1, V2, D2> ops::Mul<Quantity<V2, D2>> for Quantity<V1, D1>
+where
+    V1: ops::Mul<V2>,
+    D1: DimMul<D2>,
+{
+    type Output = Quantity<V1::Output, Product<D1, D2>>;
+
+    fn mul(self, rhs: Quantity<V2, D2>) -> Self::Output {
+        Quantity::new(self.value * rhs.value)
+    }
+}
+impl<V1, D1, V2, D2> ops::Div<Quantity<V2, D2>> for Quantity<V1, D1>
+where
+    V1: ops::Div<V2>,
+    D1: DimDiv<D2>,
+{
+    type Output = Quantity<V1::Output, Quotient<D1, D2>>;
+
+    fn div(self, rhs: Quantity<V2, D2>) -> Self::Output {
+        Quantity::new(self.value / rhs.value)
+    }
+}
+
+// Scaling by plain numbers, only for `f64` since a generic `V` would overlap with the impls above
+impl<D> ops::Mul<Quantity<f64, D>> for f64 {
+    type Output = Quantity<f64, D>;
+
+    fn mul(self, rhs: Quantity<f64, D>) -> Self::Output {
+        Quantity::new(self * rhs.value)
+    }
+}
+impl<D> ops::Mul<f64> for Quantity<f64, D> {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Quantity::new(self.value * rhs)
+    }
+}
+impl<D> ops::Div<f64> for Quantity<f64, D> {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Quantity::new(self.value / rhs)
+    }
+}
+
+// SI base units
+pub const METRE: Quantity<f64, Length> = Quantity::new(1.0);
+pub const KILOGRAM: Quantity<f64, Mass> = Quantity::new(1.0);
+pub const SECOND: Quantity<f64, Time> = Quantity::new(1.0);
+pub const AMPERE: Quantity<f64, Current> = Quantity::new(1.0);
+pub const KELVIN: Quantity<f64, Temperature> = Quantity::new(1.0);
+pub const MOLE: Quantity<f64, Amount> = Quantity::new(1.0);
+pub const CANDELA: Quantity<f64, LuminousIntensity> = Quantity::new(1.0);
+
+// SI derived units
+pub const HERTZ: Quantity<f64, Frequency> = Quantity::new(1.0);
+pub const NEWTON: Quantity<f64, Force> = Quantity::new(1.0);
+pub const PASCAL: Quantity<f64, Pressure> = Quantity::new(1.0);
+pub const JOULE: Quantity<f64, Energy> = Quantity::new(1.0);
+pub const WATT: Quantity<f64, Power> = Quantity::new(1.0);
+pub const COULOMB: Quantity<f64, Charge> = Quantity::new(1.0);
+pub const VOLT: Quantity<f64, Voltage> = Quantity::new(1.0);
+pub const OHM: Quantity<f64, Resistance> = Quantity::new(1.0);
+
+// A few non-SI units
+pub const KILOMETRE: Quantity<f64, Length> = Quantity::new(1000.0);
+pub const GRAM: Quantity<f64, Mass> = Quantity::new(0.001);
+pub const MINUTE: Quantity<f64, Time> = Quantity::new(60.0);
+pub const HOUR: Quantity<f64, Time> = Quantity::new(3600.0);
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_type_eq, integer::NegInt};
+
+    use super::*;
+
+    #[test]
+    fn test_derived_dimensions() {
+        type N2 = NegInt<Succ<Succ<Zero>>>;
+        type N3 = NegInt<Succ<Succ<Succ<Zero>>>>;
+        type P2 = PosInt<Succ<Succ<Zero>>>;
+        assert_type_eq::<Force, Dim<P1, P1, N2, Z0, Z0, Z0, Z0>>();
+        assert_type_eq::<Voltage, Dim<P2, P1, N3, NegInt<Succ<Zero>>, Z0, Z0, Z0>>();
+        assert_type_eq::<Quotient<Length, Length>, Dimensionless>();
+        assert_type_eq::<Product<Frequency, Time>, Dimensionless>();
+        assert_type_eq::<Product<Current, Resistance>, Voltage>();
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let distance = 3.0 * KILOMETRE + 500.0 * METRE;
+        let time = 20.0 * MINUTE;
+        let speed = distance / time;
+        assert!(((speed / (KILOMETRE / HOUR)).value() - 10.5).abs() < 1e-12);
+
+        let force: Quantity<f64, Force> = 2.0 * KILOGRAM * (3.0 * METRE / (SECOND * SECOND));
+        assert_eq!(force, 6.0 * NEWTON);
+        let energy: Quantity<f64, Energy> = force * (2.0 * METRE);
+        assert_eq!(energy / (4.0 * SECOND), 3.0 * WATT);
+
+        let current = 12.0 * VOLT / (4.0 * OHM);
+        assert_eq!(current, 3.0 * AMPERE);
+        assert_eq!(-current + 5.0 * AMPERE - AMPERE, AMPERE);
+        assert!(GRAM < KILOGRAM);
+    }
+
+    #[test]
+    fn test_zero_cost() {
+        assert_eq!(size_of::<Quantity<f64, Force>>(), size_of::<f64>());
+        assert_eq!(align_of::<Quantity<f32, Voltage>>(), align_of::<f32>());
+    }
+
+    #[test]
+    fn test_integer_values() {
+        let area = Quantity::<u32, Length>::new(3) * Quantity::<u32, Length>::new(4);
+        let _: Quantity<u32, Area> = area;
+        assert_eq!(area.value(), 12);
+    }
+}
@@ -0,0 +1,259 @@
+//! Vectors whose length is part of their type, as a `peano` number.
+//!
+//! The length only changes through operations that compute the new length at the type level,
+//! so e.g. `head` of an empty vector or zipping vectors of different lengths don't compile.
+
+use std::marker::PhantomData;
+
+use crate::{
+    peano::{self, CalculateAdd, Peano, Succ, ToPeano, Value, Zero},
+    type_list::ConstUsize,
+};
+
+pub struct Vect<N, T> {
+    /// Always exactly `N::VALUE` items.
+    items: Vec<T>,
+    len: PhantomData<N>,
+}
+impl<T> Vect<Zero, T> {
+    pub fn new() -> Self {
+        Self::from_vec(Vec::new())
+    }
+}
+impl<T> Default for Vect<Zero, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<N, T> Vect<N, T> {
+    fn from_vec(items: Vec<T>) -> Self {
+        Self {
+            items,
+            len: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize
+    where
+        N: Value,
+    {
+        N::VALUE
+    }
+
+    pub fn is_empty(&self) -> bool
+    where
+        N: Value,
+    {
+        N::VALUE == 0
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.items
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn push(mut self, item: T) -> Vect<Succ<N>, T> {
+        self.items.push(item);
+        Vect::from_vec(self.items)
+    }
+
+    pub fn concat<M>(mut self, other: Vect<M, T>) -> Vect<peano::Add<N, M>, T>
+    where
+        N: CalculateAdd<M>,
+    {
+        self.items.extend(other.items);
+        Vect::from_vec(self.items)
+    }
+
+    pub fn zip<U>(self, other: Vect<N, U>) -> Vect<N, (T, U)> {
+        Vect::from_vec(self.items.into_iter().zip(other.items).collect())
+    }
+
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> Vect<N, U> {
+        Vect::from_vec(self.items.into_iter().map(f).collect())
+    }
+
+    /// Creates a vector of the right length by calling `f` with every index.
+    pub fn from_fn<F: FnMut(usize) -> T>(f: F) -> Self
+    where
+        N: Value,
+    {
+        Self::from_vec((0..N::VALUE).map(f).collect())
+    }
+
+    /// Converts an array of the same length.
+    ///
+    /// A mismatch fails to compile, but only once the call is monomorphised (`cargo build`, not
+    /// `cargo check`).
+    pub fn from_array<const L: usize>(array: [T; L]) -> Self
+    where
+        N: Value,
+    {
+        // `[T; N::VALUE]` needs `generic_const_exprs`, so the length is checked separately
+        const { assert!(L == N::VALUE, "array length doesn't match the Vect length") };
+        Self::from_vec(array.into())
+    }
+
+    /// Converts into an array of the same length, a mismatch fails to compile like for
+    /// `from_array`.
+    pub fn into_array<const L: usize>(self) -> [T; L]
+    where
+        N: Value,
+    {
+        const { assert!(L == N::VALUE, "array length doesn't match the Vect length") };
+        match self.items.try_into() {
+            Ok(array) => array,
+            Err(_) => unreachable!("Vect has N::VALUE items"),
+        }
+    }
+}
+impl<N, T> Vect<Succ<N>, T> {
+    pub fn head(&self) -> &T {
+        &self.items[0]
+    }
+
+    pub fn last(&self) -> &T {
+        &self.items[self.items.len() - 1]
+    }
+
+    pub fn tail(mut self) -> (T, Vect<N, T>) {
+        let head = self.items.remove(0);
+        (head, Vect::from_vec(self.items))
+    }
+
+    pub fn pop(mut self) -> (Vect<N, T>, T) {
+        let last = self.items.pop().expect("Vect<Succ<N>, T> isn't empty");
+        (Vect::from_vec(self.items), last)
+    }
+}
+impl<N, T: Clone> Clone for Vect<N, T> {
+    fn clone(&self) -> Self {
+        Self::from_vec(self.items.clone())
+    }
+}
+impl<N, T: std::fmt::Debug> std::fmt::Debug for Vect<N, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.items).finish()
+    }
+}
+impl<N, T: PartialEq> PartialEq for Vect<N, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+impl<N, T> IntoIterator for Vect<N, T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+impl<'a, N, T> IntoIterator for &'a Vect<N, T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+/// Like `from_array` but with the length inferred from the array, up to `MAX_CONST_USIZE`.
+impl<T, const L: usize> From<[T; L]> for Vect<Peano<ConstUsize<L>>, T>
+where
+    ConstUsize<L>: ToPeano,
+{
+    fn from(array: [T; L]) -> Self {
+        Self::from_vec(array.into())
+    }
+}
+impl<N, T> From<Vect<N, T>> for Vec<T> {
+    fn from(vect: Vect<N, T>) -> Self {
+        vect.items
+    }
+}
+
+/// Creates a `Vect` of the given items, its length is inferred from the number of items.
+#[macro_export]
+macro_rules! vect {
+    ($($item:expr),* $(,)?) => {
+        $crate::vect::Vect::new()$(.push($item))*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_type_eq, binary};
+
+    use super::*;
+
+    type Three = Succ<Succ<Succ<Zero>>>;
+
+    #[test]
+    fn test_push_and_pop() {
+        let v = Vect::new().push(1).push(2).push(3);
+        let _: &Vect<Three, i32> = &v;
+        assert_eq!(v.len(), 3);
+        assert_eq!(v.as_slice(), &[1, 2, 3]);
+        assert_eq!((*v.head(), *v.last()), (1, 3));
+
+        let (v, last) = v.pop();
+        assert_eq!(last, 3);
+        let (head, v) = v.tail();
+        assert_eq!(head, 1);
+        let (v, last) = v.pop();
+        assert_eq!(last, 2);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn test_macro() {
+        let v = vect![1, 2, 3];
+        assert_eq!(v, Vect::new().push(1).push(2).push(3));
+        let empty: Vect<Zero, u8> = vect![];
+        assert_eq!(empty.len(), 0);
+    }
+
+    #[test]
+    fn test_concat() {
+        let v = vect!['a', 'b'].concat(vect!['c', 'd', 'e']);
+        assert_eq!(v.len(), 5);
+        assert_eq!(v.iter().collect::<String>(), "abcde");
+        assert_type_eq::<Vect<peano::Add<Three, Three>, u8>, Vect<Peano<binary!(x x o)>, u8>>();
+    }
+
+    #[test]
+    fn test_zip_and_map() {
+        let v = vect![1, 2, 3].zip(vect!["one", "two", "three"]);
+        assert_eq!(v, vect![(1, "one"), (2, "two"), (3, "three")]);
+        assert_eq!(
+            v.map(|(n, s)| s.repeat(n)),
+            vect![
+                "one".to_string(),
+                "twotwo".to_string(),
+                "threethreethree".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arrays() {
+        let v = Vect::<Three, _>::from_array([1, 2, 3]);
+        assert_eq!(v, vect![1, 2, 3]);
+        let array: [i32; 3] = v.into_array();
+        assert_eq!(array, [1, 2, 3]);
+
+        let v = Vect::from(['a', 'b', 'c']);
+        let _: &Vect<Three, char> = &v;
+        assert_eq!(v.pop().1, 'c');
+
+        let squares = Vect::<Three, usize>::from_fn(|i| i * i);
+        assert_eq!(Vec::from(squares), vec![0, 1, 4]);
+    }
+}
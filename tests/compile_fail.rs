@@ -0,0 +1,13 @@
+#[test]
+fn compile_fail() {
+    // print full type names, otherwise long ones are written to randomly named files which end up
+    // in the expected output
+    let rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
+    std::env::set_var("RUSTFLAGS", format!("{rustflags} --verbose"));
+
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/peano/*.rs");
+    t.compile_fail("tests/ui/units/*.rs");
+    t.compile_fail("tests/ui/vect/*.rs");
+    t.pass("tests/ui/pass/*.rs");
+}
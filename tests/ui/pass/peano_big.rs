@@ -0,0 +1,9 @@
+#![recursion_limit = "2048"]
+
+use exploring_rust::{peano, peano::*};
+
+// how big a literal can get is up to the `recursion_limit` of the caller
+fn main() {
+    assert_eq!(<peano!(1234) as Value>::VALUE, 1234);
+    assert_eq!(<Sub<peano!(1234), peano!(0x400)> as Value>::VALUE, 210);
+}
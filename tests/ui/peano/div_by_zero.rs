@@ -0,0 +1,7 @@
+use exploring_rust::peano::*;
+
+type Four = Succ<Succ<Succ<Succ<Zero>>>>;
+
+fn main() {
+    let _ = <Div<Four, Zero> as Value>::VALUE;
+}
@@ -0,0 +1,6 @@
+use exploring_rust::peano;
+
+type TooBig = peano!(18446744073709551616);
+type Suffixed = peano!(3u8);
+
+fn main() {}
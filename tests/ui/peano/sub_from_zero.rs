@@ -0,0 +1,5 @@
+use exploring_rust::peano::*;
+
+fn main() {
+    let _ = <Sub<Zero, Succ<Zero>> as Value>::VALUE;
+}
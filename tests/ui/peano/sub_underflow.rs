@@ -0,0 +1,8 @@
+use exploring_rust::peano::*;
+
+type Two = Succ<Succ<Zero>>;
+type Three = Succ<Two>;
+
+fn main() {
+    let _ = <Sub<Two, Three> as Value>::VALUE;
+}
@@ -0,0 +1,5 @@
+use exploring_rust::units::*;
+
+fn main() {
+    let _ = 3.0 * METRE + 2.0 * SECOND;
+}
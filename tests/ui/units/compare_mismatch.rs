@@ -0,0 +1,5 @@
+use exploring_rust::units::*;
+
+fn main() {
+    let _ = 1.0 * WATT < 1.0 * JOULE;
+}
@@ -0,0 +1,6 @@
+use exploring_rust::units::*;
+
+fn main() {
+    // energy, not force
+    let _: Quantity<f64, Force> = 2.0 * NEWTON * (3.0 * METRE);
+}
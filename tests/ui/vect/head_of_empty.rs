@@ -0,0 +1,6 @@
+use exploring_rust::vect;
+
+fn main() {
+    let v = vect![1].pop().0;
+    let _ = v.head();
+}
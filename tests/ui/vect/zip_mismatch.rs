@@ -0,0 +1,5 @@
+use exploring_rust::vect;
+
+fn main() {
+    let _ = vect![1, 2].zip(vect!["a", "b", "c"]);
+}